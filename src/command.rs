@@ -1,10 +1,20 @@
+use core::fmt;
+use core::ops::Deref;
+
 use bitflags::bitflags;
 
 use super::packer::{Packer, Unpacker};
+use crate::decoder::DEFAULT_BUFFER_LEN;
 
-const CRC16: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_XMODEM);
+pub(crate) const CRC16: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_XMODEM);
 const FRAME_END: u8 = 3;
 const FRAME_START_SHORT: u8 = 2;
+const FRAME_START_LONG: u8 = 3;
+
+/// The number of data bytes per chunk used by the transfer helpers unless
+/// configured otherwise. Chunks of this size do not fit into short frames,
+/// hence they are sent using long frames.
+pub const DEFAULT_CHUNK_SIZE: usize = 384;
 
 /// Errors that can occur during command encoding.
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
@@ -13,6 +23,15 @@ const FRAME_START_SHORT: u8 = 2;
 pub enum EncodeError {
     #[error("the output buffer provided for encoding is too small")]
     BufferTooSmall,
+
+    #[error("the command payload exceeds the maximum frame length")]
+    PayloadTooLarge,
+
+    #[error("the CAN frame has more than 8 data bytes or an out-of-range ID")]
+    InvalidCanFrame,
+
+    #[error("a forwarded command cannot forward another command")]
+    NestedForwardCan,
 }
 
 /// Errors that can occur during command reply decoding.
@@ -32,19 +51,104 @@ pub enum DecodeError {
     #[error("the frame structure is frame")]
     InvalidFrame,
 
+    #[error("the reply contains more elements than fit into the reply structure")]
+    CapacityExceeded,
+
     #[error("internal error, something is broken in the Decoding logic")]
     Internal,
 }
 
 #[repr(u8)]
 enum CommandId {
+    JumpToBootloader = 1,
+    EraseNewApp = 2,
+    WriteNewAppData = 3,
     GetValues = 4,
     SetCurrent = 6,
     SetCurrentBrake = 7,
     SetRpm = 8,
     SetHandbrake = 10,
+    SamplePrint = 19,
+    RotorPosition = 22,
+    ExperimentSample = 23,
+    DetectMotorParam = 24,
+    DetectMotorRL = 25,
+    DetectMotorFluxLinkage = 26,
+    DetectEncoder = 27,
+    DetectHallFoc = 28,
+    Reboot = 29,
+    GetDecodedPpm = 31,
+    GetDecodedAdc = 32,
+    GetDecodedChuk = 33,
     ForwardCan = 34,
+    SetChuckData = 35,
+    CustomAppData = 36,
+    GetValuesSetup = 47,
     GetValuesSelective = 50,
+    GetValuesSetupSelective = 51,
+    DetectMotorFluxLinkageOpenloop = 57,
+    DetectApplyAllFoc = 58,
+    JumpToBootloaderAllCan = 59,
+    EraseNewAppAllCan = 60,
+    WriteNewAppDataAllCan = 61,
+    AppDisableOutput = 63,
+    GetImuData = 65,
+    EraseBootloader = 73,
+    EraseBootloaderAllCan = 74,
+    PlotInit = 75,
+    PlotData = 76,
+    PlotAddGraph = 77,
+    PlotSetGraph = 78,
+    GetDecodedBalance = 79,
+    WriteNewAppDataLzo = 81,
+    WriteNewAppDataAllCanLzo = 82,
+    CanFwdFrame = 85,
+    SetBatteryCut = 86,
+    SetBleName = 87,
+    SetBlePin = 88,
+    SetCanMode = 89,
+    GetImuCalibration = 90,
+    GetCustomConfigXml = 92,
+    GetCustomConfig = 93,
+    GetCustomConfigDefault = 94,
+    SetCustomConfig = 95,
+    BmsGetValues = 96,
+    BmsSetChargeAllowed = 97,
+    BmsSetBalanceOverride = 98,
+    BmsResetCounters = 99,
+    BmsForceBalance = 100,
+    BmsZeroCurrentOffset = 101,
+    JumpToBootloaderHw = 102,
+    EraseNewAppHw = 103,
+    WriteNewAppDataHw = 104,
+    EraseBootloaderHw = 105,
+    JumpToBootloaderAllCanHw = 106,
+    EraseNewAppAllCanHw = 107,
+    WriteNewAppDataAllCanHw = 108,
+    EraseBootloaderAllCanHw = 109,
+    SetOdometer = 110,
+    GetStats = 128,
+    ResetStats = 129,
+    LispReadCode = 130,
+    LispWriteCode = 131,
+    LispEraseCode = 132,
+    LispSetRunning = 133,
+    LispGetStats = 134,
+    LispPrint = 135,
+    LispReplCmd = 138,
+    FileList = 140,
+    FileRead = 141,
+    FileWrite = 142,
+    FileMkdir = 143,
+    FileRemove = 144,
+    LogStart = 145,
+    LogStop = 146,
+    LogConfigField = 147,
+    LogDataF32 = 148,
+    GetGnss = 150,
+    LogDataF64 = 151,
+    Shutdown = 156,
+    CanUpdateBaudAll = 158,
 }
 
 impl TryFrom<u8> for CommandId {
@@ -52,13 +156,121 @@ impl TryFrom<u8> for CommandId {
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
+            id if id == CommandId::JumpToBootloader as u8 => Ok(CommandId::JumpToBootloader),
+            id if id == CommandId::EraseNewApp as u8 => Ok(CommandId::EraseNewApp),
+            id if id == CommandId::WriteNewAppData as u8 => Ok(CommandId::WriteNewAppData),
             id if id == CommandId::GetValues as u8 => Ok(CommandId::GetValues),
             id if id == CommandId::SetCurrent as u8 => Ok(CommandId::SetCurrent),
             id if id == CommandId::SetCurrentBrake as u8 => Ok(CommandId::SetCurrentBrake),
             id if id == CommandId::SetRpm as u8 => Ok(CommandId::SetRpm),
             id if id == CommandId::SetHandbrake as u8 => Ok(CommandId::SetHandbrake),
+            id if id == CommandId::SamplePrint as u8 => Ok(CommandId::SamplePrint),
+            id if id == CommandId::RotorPosition as u8 => Ok(CommandId::RotorPosition),
+            id if id == CommandId::ExperimentSample as u8 => Ok(CommandId::ExperimentSample),
+            id if id == CommandId::DetectMotorParam as u8 => Ok(CommandId::DetectMotorParam),
+            id if id == CommandId::DetectMotorRL as u8 => Ok(CommandId::DetectMotorRL),
+            id if id == CommandId::DetectMotorFluxLinkage as u8 => {
+                Ok(CommandId::DetectMotorFluxLinkage)
+            }
+            id if id == CommandId::DetectEncoder as u8 => Ok(CommandId::DetectEncoder),
+            id if id == CommandId::DetectHallFoc as u8 => Ok(CommandId::DetectHallFoc),
+            id if id == CommandId::Reboot as u8 => Ok(CommandId::Reboot),
+            id if id == CommandId::GetDecodedPpm as u8 => Ok(CommandId::GetDecodedPpm),
+            id if id == CommandId::GetDecodedAdc as u8 => Ok(CommandId::GetDecodedAdc),
+            id if id == CommandId::GetDecodedChuk as u8 => Ok(CommandId::GetDecodedChuk),
             id if id == CommandId::ForwardCan as u8 => Ok(CommandId::ForwardCan),
+            id if id == CommandId::SetChuckData as u8 => Ok(CommandId::SetChuckData),
+            id if id == CommandId::CustomAppData as u8 => Ok(CommandId::CustomAppData),
+            id if id == CommandId::GetValuesSetup as u8 => Ok(CommandId::GetValuesSetup),
             id if id == CommandId::GetValuesSelective as u8 => Ok(CommandId::GetValuesSelective),
+            id if id == CommandId::GetValuesSetupSelective as u8 => {
+                Ok(CommandId::GetValuesSetupSelective)
+            }
+            id if id == CommandId::DetectMotorFluxLinkageOpenloop as u8 => {
+                Ok(CommandId::DetectMotorFluxLinkageOpenloop)
+            }
+            id if id == CommandId::DetectApplyAllFoc as u8 => Ok(CommandId::DetectApplyAllFoc),
+            id if id == CommandId::JumpToBootloaderAllCan as u8 => {
+                Ok(CommandId::JumpToBootloaderAllCan)
+            }
+            id if id == CommandId::EraseNewAppAllCan as u8 => Ok(CommandId::EraseNewAppAllCan),
+            id if id == CommandId::WriteNewAppDataAllCan as u8 => {
+                Ok(CommandId::WriteNewAppDataAllCan)
+            }
+            id if id == CommandId::AppDisableOutput as u8 => Ok(CommandId::AppDisableOutput),
+            id if id == CommandId::GetImuData as u8 => Ok(CommandId::GetImuData),
+            id if id == CommandId::EraseBootloader as u8 => Ok(CommandId::EraseBootloader),
+            id if id == CommandId::EraseBootloaderAllCan as u8 => {
+                Ok(CommandId::EraseBootloaderAllCan)
+            }
+            id if id == CommandId::PlotInit as u8 => Ok(CommandId::PlotInit),
+            id if id == CommandId::PlotData as u8 => Ok(CommandId::PlotData),
+            id if id == CommandId::PlotAddGraph as u8 => Ok(CommandId::PlotAddGraph),
+            id if id == CommandId::PlotSetGraph as u8 => Ok(CommandId::PlotSetGraph),
+            id if id == CommandId::GetDecodedBalance as u8 => Ok(CommandId::GetDecodedBalance),
+            id if id == CommandId::WriteNewAppDataLzo as u8 => Ok(CommandId::WriteNewAppDataLzo),
+            id if id == CommandId::WriteNewAppDataAllCanLzo as u8 => {
+                Ok(CommandId::WriteNewAppDataAllCanLzo)
+            }
+            id if id == CommandId::CanFwdFrame as u8 => Ok(CommandId::CanFwdFrame),
+            id if id == CommandId::SetBatteryCut as u8 => Ok(CommandId::SetBatteryCut),
+            id if id == CommandId::SetBleName as u8 => Ok(CommandId::SetBleName),
+            id if id == CommandId::SetBlePin as u8 => Ok(CommandId::SetBlePin),
+            id if id == CommandId::SetCanMode as u8 => Ok(CommandId::SetCanMode),
+            id if id == CommandId::GetImuCalibration as u8 => Ok(CommandId::GetImuCalibration),
+            id if id == CommandId::GetCustomConfigXml as u8 => Ok(CommandId::GetCustomConfigXml),
+            id if id == CommandId::GetCustomConfig as u8 => Ok(CommandId::GetCustomConfig),
+            id if id == CommandId::GetCustomConfigDefault as u8 => {
+                Ok(CommandId::GetCustomConfigDefault)
+            }
+            id if id == CommandId::SetCustomConfig as u8 => Ok(CommandId::SetCustomConfig),
+            id if id == CommandId::BmsGetValues as u8 => Ok(CommandId::BmsGetValues),
+            id if id == CommandId::BmsSetChargeAllowed as u8 => Ok(CommandId::BmsSetChargeAllowed),
+            id if id == CommandId::BmsSetBalanceOverride as u8 => {
+                Ok(CommandId::BmsSetBalanceOverride)
+            }
+            id if id == CommandId::BmsResetCounters as u8 => Ok(CommandId::BmsResetCounters),
+            id if id == CommandId::BmsForceBalance as u8 => Ok(CommandId::BmsForceBalance),
+            id if id == CommandId::BmsZeroCurrentOffset as u8 => {
+                Ok(CommandId::BmsZeroCurrentOffset)
+            }
+            id if id == CommandId::JumpToBootloaderHw as u8 => Ok(CommandId::JumpToBootloaderHw),
+            id if id == CommandId::EraseNewAppHw as u8 => Ok(CommandId::EraseNewAppHw),
+            id if id == CommandId::WriteNewAppDataHw as u8 => Ok(CommandId::WriteNewAppDataHw),
+            id if id == CommandId::EraseBootloaderHw as u8 => Ok(CommandId::EraseBootloaderHw),
+            id if id == CommandId::JumpToBootloaderAllCanHw as u8 => {
+                Ok(CommandId::JumpToBootloaderAllCanHw)
+            }
+            id if id == CommandId::EraseNewAppAllCanHw as u8 => Ok(CommandId::EraseNewAppAllCanHw),
+            id if id == CommandId::WriteNewAppDataAllCanHw as u8 => {
+                Ok(CommandId::WriteNewAppDataAllCanHw)
+            }
+            id if id == CommandId::EraseBootloaderAllCanHw as u8 => {
+                Ok(CommandId::EraseBootloaderAllCanHw)
+            }
+            id if id == CommandId::SetOdometer as u8 => Ok(CommandId::SetOdometer),
+            id if id == CommandId::GetStats as u8 => Ok(CommandId::GetStats),
+            id if id == CommandId::ResetStats as u8 => Ok(CommandId::ResetStats),
+            id if id == CommandId::LispReadCode as u8 => Ok(CommandId::LispReadCode),
+            id if id == CommandId::LispWriteCode as u8 => Ok(CommandId::LispWriteCode),
+            id if id == CommandId::LispEraseCode as u8 => Ok(CommandId::LispEraseCode),
+            id if id == CommandId::LispSetRunning as u8 => Ok(CommandId::LispSetRunning),
+            id if id == CommandId::LispGetStats as u8 => Ok(CommandId::LispGetStats),
+            id if id == CommandId::LispPrint as u8 => Ok(CommandId::LispPrint),
+            id if id == CommandId::LispReplCmd as u8 => Ok(CommandId::LispReplCmd),
+            id if id == CommandId::FileList as u8 => Ok(CommandId::FileList),
+            id if id == CommandId::FileRead as u8 => Ok(CommandId::FileRead),
+            id if id == CommandId::FileWrite as u8 => Ok(CommandId::FileWrite),
+            id if id == CommandId::FileMkdir as u8 => Ok(CommandId::FileMkdir),
+            id if id == CommandId::FileRemove as u8 => Ok(CommandId::FileRemove),
+            id if id == CommandId::LogStart as u8 => Ok(CommandId::LogStart),
+            id if id == CommandId::LogStop as u8 => Ok(CommandId::LogStop),
+            id if id == CommandId::LogConfigField as u8 => Ok(CommandId::LogConfigField),
+            id if id == CommandId::LogDataF32 as u8 => Ok(CommandId::LogDataF32),
+            id if id == CommandId::GetGnss as u8 => Ok(CommandId::GetGnss),
+            id if id == CommandId::LogDataF64 as u8 => Ok(CommandId::LogDataF64),
+            id if id == CommandId::Shutdown as u8 => Ok(CommandId::Shutdown),
+            id if id == CommandId::CanUpdateBaudAll as u8 => Ok(CommandId::CanUpdateBaudAll),
             id => Err(DecodeError::UnknownPacket { id }),
         }
     }
@@ -107,6 +319,142 @@ bitflags! {
     }
 }
 
+/// A bitmask used with [`Command::GetValuesSetupSelective`] to request specific
+/// setup telemetry fields. Each flag corresponds to a field in the
+/// [`SetupValues`] struct.
+///
+/// # Example
+///
+/// ```rust
+/// use vesc::SetupValuesMask;
+///
+/// let mask = SetupValuesMask::SPEED | SetupValuesMask::BATTERY_LEVEL;
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SetupValuesMask(u32);
+
+bitflags! {
+    impl SetupValuesMask: u32 {
+        const TEMP_MOSFET           = 1 << 0;
+        const TEMP_MOTOR            = 1 << 1;
+        const CURRENT_MOTOR         = 1 << 2;
+        const CURRENT_INPUT         = 1 << 3;
+        const DUTY_CYCLE            = 1 << 4;
+        const RPM                   = 1 << 5;
+        const SPEED                 = 1 << 6;
+        const VOLTAGE_IN            = 1 << 7;
+        const BATTERY_LEVEL         = 1 << 8;
+        const AMP_HOURS             = 1 << 9;
+        const AMP_HOURS_CHARGED     = 1 << 10;
+        const WATT_HOURS            = 1 << 11;
+        const WATT_HOURS_CHARGED    = 1 << 12;
+        const DISTANCE              = 1 << 13;
+        const DISTANCE_ABS          = 1 << 14;
+        const PID_POS               = 1 << 15;
+        const FAULT_CODE            = 1 << 16;
+        const CONTROLLER_ID         = 1 << 17;
+        const NUM_VESCS             = 1 << 18;
+        const BATTERY_WATT_HOURS    = 1 << 19;
+        const ODOMETER              = 1 << 20;
+        const UPTIME                = 1 << 21;
+    }
+}
+
+/// A bitmask used with [`Command::GetImuData`] to request specific IMU fields.
+/// Each flag corresponds to a field in the [`ImuData`] struct.
+///
+/// # Example
+///
+/// ```rust
+/// use vesc::ImuMask;
+///
+/// let mask = ImuMask::ROLL | ImuMask::PITCH | ImuMask::YAW;
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ImuMask(u16);
+
+bitflags! {
+    impl ImuMask: u16 {
+        const ROLL                  = 1 << 0;
+        const PITCH                 = 1 << 1;
+        const YAW                   = 1 << 2;
+        const ACC_X                 = 1 << 3;
+        const ACC_Y                 = 1 << 4;
+        const ACC_Z                 = 1 << 5;
+        const GYRO_X                = 1 << 6;
+        const GYRO_Y                = 1 << 7;
+        const GYRO_Z                = 1 << 8;
+        const MAG_X                 = 1 << 9;
+        const MAG_Y                 = 1 << 10;
+        const MAG_Z                 = 1 << 11;
+        const Q0                    = 1 << 12;
+        const Q1                    = 1 << 13;
+        const Q2                    = 1 << 14;
+        const Q3                    = 1 << 15;
+    }
+}
+
+/// A bitmask used with [`Command::GetStats`] to request specific statistics.
+/// Each flag corresponds to a field in the [`StatsValues`] struct.
+///
+/// # Example
+///
+/// ```rust
+/// use vesc::StatsMask;
+///
+/// let mask = StatsMask::SPEED_AVG | StatsMask::SPEED_MAX | StatsMask::COUNT_TIME;
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StatsMask(u16);
+
+bitflags! {
+    impl StatsMask: u16 {
+        const SPEED_AVG             = 1 << 0;
+        const SPEED_MAX             = 1 << 1;
+        const POWER_AVG             = 1 << 2;
+        const POWER_MAX             = 1 << 3;
+        const CURRENT_AVG           = 1 << 4;
+        const CURRENT_MAX           = 1 << 5;
+        const TEMP_MOSFET_AVG       = 1 << 6;
+        const TEMP_MOSFET_MAX       = 1 << 7;
+        const TEMP_MOTOR_AVG        = 1 << 8;
+        const TEMP_MOTOR_MAX        = 1 << 9;
+        const COUNT_TIME            = 1 << 10;
+    }
+}
+
+/// A bitmask used with [`Command::GetGnss`] to request specific GNSS fields.
+/// Each flag corresponds to a field in the [`GnssData`] struct.
+///
+/// # Example
+///
+/// ```rust
+/// use vesc::GnssMask;
+///
+/// let mask = GnssMask::LATITUDE | GnssMask::LONGITUDE | GnssMask::HEIGHT;
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GnssMask(u16);
+
+bitflags! {
+    impl GnssMask: u16 {
+        const LATITUDE              = 1 << 0;
+        const LONGITUDE             = 1 << 1;
+        const HEIGHT                = 1 << 2;
+        const SPEED                 = 1 << 3;
+        const HDOP                  = 1 << 4;
+        const MS_TODAY              = 1 << 5;
+        const YEAR                  = 1 << 6;
+        const MONTH                 = 1 << 7;
+        const DAY                   = 1 << 8;
+        const AGE                   = 1 << 9;
+    }
+}
+
 /// Commands that can be sent to a VESC controller.
 ///
 /// Each variant represents a different operation that can be performed on the
@@ -123,6 +471,20 @@ bitflags! {
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Command<'a> {
+    /// Makes the VESC jump to its bootloader, which then flashes the firmware
+    /// previously written with [`WriteNewAppData`] and boots into it.
+    ///
+    /// [`WriteNewAppData`]: Self::WriteNewAppData
+    JumpToBootloader,
+
+    /// Erases the flash area reserved for a new firmware image. Takes the size
+    /// of the image in bytes, including the size and checksum header.
+    EraseNewApp(u32),
+
+    /// Writes a chunk of the new firmware image. Takes the offset of the chunk
+    /// within the image and the chunk data.
+    WriteNewAppData(u32, &'a [u8]),
+
     /// Requests the complete set of telemetry data from the VESC.
     GetValues,
 
@@ -140,23 +502,351 @@ pub enum Command<'a> {
     /// Sets the handbrake current in amperes.
     SetHandbrake(f32),
 
+    /// Samples phase currents and voltages at the switching frequency. Takes
+    /// the sampling mode, the number of samples and the decimation, i.e. how
+    /// many switching cycles to skip between samples. The samples are sent as
+    /// [`CommandReply::SamplePrint`], one reply per sample.
+    SamplePrint(SamplingMode, u16, u8),
+
+    /// Runs the BLDC motor parameter detection. Takes the detection current in
+    /// amperes, the minimum RPM and the low duty cycle.
+    DetectMotorParam(f32, f32, f32),
+
+    /// Measures the motor resistance and inductance.
+    DetectMotorRL,
+
+    /// Measures the motor flux linkage by spinning the motor with the BLDC
+    /// commutation.
+    DetectMotorFluxLinkage(FluxLinkageParams),
+
+    /// Detects the encoder offset, ratio and direction. Takes the detection
+    /// current in amperes.
+    DetectEncoder(f32),
+
+    /// Detects the hall sensor table for FOC. Takes the detection current in
+    /// amperes.
+    DetectHallFoc(f32),
+
+    /// Reboots the VESC.
+    Reboot,
+
+    /// Requests the input decoded by the PPM app.
+    GetDecodedPpm,
+
+    /// Requests the input decoded by the ADC app.
+    GetDecodedAdc,
+
+    /// Requests the input decoded by the Nunchuk app.
+    GetDecodedChuk,
+
     /// Forwards a command to another VESC controller on the CAN bus. Takes the
-    /// target controller ID and the command to forward.
+    /// target controller ID and the command to forward. The command is
+    /// forwarded to every controller on the bus if the ID is `255`.
+    ///
+    /// Forwarding a `ForwardCan` is rejected with
+    /// [`EncodeError::NestedForwardCan`]. Use [`CanForward`] to forward a
+    /// command to several controllers at once.
+    ///
+    /// [`CanForward`]: crate::CanForward
     ForwardCan(
         u8,
         #[cfg_attr(feature = "defmt", defmt(Debug2Format))] &'a Command<'a>,
     ),
 
+    /// Feeds remote control input to the Nunchuk app, e.g. from a custom
+    /// remote connected over UART.
+    SetChuckData(ChuckData),
+
+    /// Sends opaque data to a custom application running on the VESC, e.g. a
+    /// C app or a LispBM script.
+    CustomAppData(&'a [u8]),
+
+    /// Requests the complete set of setup telemetry data, i.e. telemetry
+    /// aggregated over every controller on the CAN bus.
+    GetValuesSetup,
+
     /// Requests a subset of telemetry data specified by a [`ValuesMask`]
     /// bitmask. Using a mask reduces communication overhead and processing time
     /// compared to [`GetValues`], making it more efficient when only selected
     /// data fields are needed.
     GetValuesSelective(ValuesMask),
+
+    /// Requests a subset of setup telemetry data specified by a
+    /// [`SetupValuesMask`] bitmask.
+    GetValuesSetupSelective(SetupValuesMask),
+
+    /// Measures the motor flux linkage by spinning the motor in open loop.
+    DetectMotorFluxLinkageOpenloop(FluxLinkageOpenloopParams),
+
+    /// Runs the complete FOC detection, i.e. resistance, inductance, flux
+    /// linkage and sensors, and applies the results to the configuration.
+    DetectApplyAllFoc(ApplyAllFocParams),
+
+    /// Same as [`JumpToBootloader`], but also applies to every controller on
+    /// the CAN bus.
+    ///
+    /// [`JumpToBootloader`]: Self::JumpToBootloader
+    JumpToBootloaderAllCan,
+
+    /// Same as [`EraseNewApp`], but also applies to every controller on the
+    /// CAN bus.
+    ///
+    /// [`EraseNewApp`]: Self::EraseNewApp
+    EraseNewAppAllCan(u32),
+
+    /// Same as [`WriteNewAppData`], but also applies to every controller on the
+    /// CAN bus.
+    ///
+    /// [`WriteNewAppData`]: Self::WriteNewAppData
+    WriteNewAppDataAllCan(u32, &'a [u8]),
+
+    /// Disables the output of the running app, e.g. PPM or ADC input, for the
+    /// given time in milliseconds. Takes whether to also disable the output of
+    /// every controller on the CAN bus, and the time. A time of `0` re-enables
+    /// the output, and a negative time disables it until re-enabled.
+    AppDisableOutput(bool, i32),
+
+    /// Requests the IMU data fields specified by an [`ImuMask`] bitmask.
+    GetImuData(ImuMask),
+
+    /// Erases the flash area of the bootloader, which must be done before a
+    /// new bootloader is written.
+    EraseBootloader,
+
+    /// Same as [`EraseBootloader`], but also applies to every controller on
+    /// the CAN bus.
+    ///
+    /// [`EraseBootloader`]: Self::EraseBootloader
+    EraseBootloaderAllCan,
+
+    /// Requests the state of the balance app.
+    GetDecodedBalance,
+
+    /// Same as [`WriteNewAppData`], but with LZO1X compressed chunk data.
+    /// Takes the offset of the chunk within the image, the length of the
+    /// decompressed chunk and the compressed chunk data.
+    ///
+    /// [`WriteNewAppData`]: Self::WriteNewAppData
+    WriteNewAppDataLzo(u32, u16, &'a [u8]),
+
+    /// Same as [`WriteNewAppDataLzo`], but also applies to every controller on
+    /// the CAN bus.
+    ///
+    /// [`WriteNewAppDataLzo`]: Self::WriteNewAppDataLzo
+    WriteNewAppDataAllCanLzo(u32, u16, &'a [u8]),
+
+    /// Sends a raw frame on the CAN bus, e.g. to a peripheral that is not a
+    /// VESC. Takes the CAN ID, whether the ID is extended (29 bits) rather
+    /// than standard (11 bits), and up to 8 data bytes.
+    CanFwdFrame(u32, bool, &'a [u8]),
+
+    /// Sets the battery cutoff voltages, i.e. the voltages between which the
+    /// input current is limited to protect the battery.
+    SetBatteryCut(BatteryCutParams),
+
+    /// Renames the BLE module.
+    SetBleName(&'a str),
+
+    /// Sets the PIN of the BLE module.
+    SetBlePin(&'a str),
+
+    /// Sets the CAN mode. Takes the mode, whether to store it in the
+    /// configuration, so that it persists across reboots, and whether the
+    /// controller should acknowledge the change with a
+    /// [`CommandReply::SetCanMode`].
+    SetCanMode(CanMode, bool, bool),
+
+    /// Requests the IMU calibration. Takes the yaw angle in degrees the
+    /// calibration is relative to.
+    GetImuCalibration(f32),
+
+    /// Requests a chunk of the compressed XML describing a custom config, e.g.
+    /// of a package. Takes the config index, the offset of the chunk within
+    /// the XML and the chunk length. See [`ConfigXmlDownload`] for downloading
+    /// the complete XML.
+    ///
+    /// [`ConfigXmlDownload`]: crate::ConfigXmlDownload
+    GetCustomConfigXml(u8, u32, u32),
+
+    /// Requests the serialized values of a custom config. Takes the config
+    /// index.
+    GetCustomConfig(u8),
+
+    /// Requests the serialized default values of a custom config. Takes the
+    /// config index.
+    GetCustomConfigDefault(u8),
+
+    /// Writes the serialized values of a custom config. Takes the config index
+    /// and the values.
+    SetCustomConfig(u8, &'a [u8]),
+
+    /// Requests the values of the BMS.
+    BmsGetValues,
+
+    /// Allows or prohibits charging the battery.
+    BmsSetChargeAllowed(bool),
+
+    /// Overrides the balancing of a single cell. Takes the cell index and the
+    /// override mode, where `0` removes the override.
+    BmsSetBalanceOverride(u8, u8),
+
+    /// Resets the counters of the BMS. Takes whether to reset the amp hour
+    /// counter and whether to reset the watt hour counter.
+    BmsResetCounters(bool, bool),
+
+    /// Forces balancing of all cells, regardless of the balancing thresholds.
+    BmsForceBalance(bool),
+
+    /// Calibrates the current measurement of the BMS by taking the present
+    /// current as zero. Make sure no current is flowing.
+    BmsZeroCurrentOffset,
+
+    /// Same as [`JumpToBootloader`], but only applies to devices of the given
+    /// hardware type.
+    ///
+    /// [`JumpToBootloader`]: Self::JumpToBootloader
+    JumpToBootloaderHw(HwType),
+
+    /// Same as [`EraseNewApp`], but only applies to devices of the given
+    /// hardware type.
+    ///
+    /// [`EraseNewApp`]: Self::EraseNewApp
+    EraseNewAppHw(HwType, u32),
+
+    /// Same as [`WriteNewAppData`], but only applies to devices of the given
+    /// hardware type.
+    ///
+    /// [`WriteNewAppData`]: Self::WriteNewAppData
+    WriteNewAppDataHw(HwType, u32, &'a [u8]),
+
+    /// Same as [`EraseBootloader`], but only applies to devices of the given
+    /// hardware type.
+    ///
+    /// [`EraseBootloader`]: Self::EraseBootloader
+    EraseBootloaderHw(HwType),
+
+    /// Same as [`JumpToBootloaderAllCan`], but only applies to devices of the
+    /// given hardware type.
+    ///
+    /// [`JumpToBootloaderAllCan`]: Self::JumpToBootloaderAllCan
+    JumpToBootloaderAllCanHw(HwType),
+
+    /// Same as [`EraseNewAppAllCan`], but only applies to devices of the given
+    /// hardware type.
+    ///
+    /// [`EraseNewAppAllCan`]: Self::EraseNewAppAllCan
+    EraseNewAppAllCanHw(HwType, u32),
+
+    /// Same as [`WriteNewAppDataAllCan`], but only applies to devices of the
+    /// given hardware type.
+    ///
+    /// [`WriteNewAppDataAllCan`]: Self::WriteNewAppDataAllCan
+    WriteNewAppDataAllCanHw(HwType, u32, &'a [u8]),
+
+    /// Same as [`EraseBootloaderAllCan`], but only applies to devices of the
+    /// given hardware type.
+    ///
+    /// [`EraseBootloaderAllCan`]: Self::EraseBootloaderAllCan
+    EraseBootloaderAllCanHw(HwType),
+
+    /// Sets the odometer to the given distance in meters.
+    SetOdometer(u32),
+
+    /// Requests the statistics specified by a [`StatsMask`] bitmask. The
+    /// statistics are collected since boot or since the last [`ResetStats`].
+    ///
+    /// [`ResetStats`]: Self::ResetStats
+    GetStats(StatsMask),
+
+    /// Resets the statistics. Takes whether the controller should acknowledge
+    /// the reset with a [`CommandReply::ResetStats`].
+    ResetStats(bool),
+
+    /// Reads a part of the stored LispBM code. Takes the offset within the
+    /// code and the number of bytes to read.
+    LispReadCode(u32, u32),
+
+    /// Writes a part of the LispBM code. Takes the offset within the code and
+    /// the data. The code area must be erased with [`LispEraseCode`] first.
+    ///
+    /// [`LispEraseCode`]: Self::LispEraseCode
+    LispWriteCode(u32, &'a [u8]),
+
+    /// Erases the LispBM code area. Takes the size of the code to be written
+    /// afterwards, or `-1` to erase the whole area.
+    LispEraseCode(i32),
+
+    /// Starts or stops the LispBM script.
+    LispSetRunning(bool),
+
+    /// Requests the LispBM runtime statistics. Takes whether to report all
+    /// global bindings instead of only the numeric ones.
+    LispGetStats(bool),
+
+    /// Evaluates an expression in the LispBM REPL. The result is printed,
+    /// i.e. sent as a [`CommandReply::LispPrint`].
+    LispReplCmd(&'a str),
+
+    /// Lists the entries of a directory on the file system. Takes the path of
+    /// the directory and the name of the entry to continue the listing after,
+    /// or an empty string to start from the beginning. The reply indicates
+    /// whether more entries are left.
+    FileList(&'a str, &'a str),
+
+    /// Reads a chunk of a file. Takes the path of the file and the offset to
+    /// read from. The size of the chunk is chosen by the controller.
+    FileRead(&'a str, u32),
+
+    /// Writes a chunk of a file.
+    FileWrite(FileWriteParams<'a>),
+
+    /// Creates a directory, including missing parent directories.
+    FileMkdir(&'a str),
+
+    /// Removes a file or an empty directory.
+    FileRemove(&'a str),
+
+    /// Starts logging. The fields must be configured with [`LogConfigField`]
+    /// first. The samples are sent as [`CommandReply::LogDataF32`] or
+    /// [`CommandReply::LogDataF64`].
+    ///
+    /// [`LogConfigField`]: Self::LogConfigField
+    LogStart(LogStartParams),
+
+    /// Stops logging.
+    LogStop,
+
+    /// Configures a log field.
+    LogConfigField(LogField<'a>),
+
+    /// Requests the GNSS fields specified by a [`GnssMask`] bitmask.
+    GetGnss(GnssMask),
+
+    /// Shuts the VESC down, if supported by the hardware.
+    Shutdown,
+
+    /// Changes the baud rate of every controller on the CAN bus at once. Takes
+    /// the new baud rate and the delay in milliseconds after which it is
+    /// applied, which gives every controller the chance to receive the change
+    /// at the old baud rate first.
+    CanUpdateBaudAll(CanBaud, u16),
 }
 
 impl<'a> Command<'a> {
     fn pack_into(&self, packer: &mut Packer) -> Result<(), EncodeError> {
         match self {
+            Self::JumpToBootloader => {
+                packer.pack_u8(CommandId::JumpToBootloader as u8)?;
+            }
+            Self::EraseNewApp(size) => {
+                packer.pack_u8(CommandId::EraseNewApp as u8)?;
+                packer.pack_u32(*size)?;
+            }
+            Self::WriteNewAppData(offset, data) => {
+                packer.pack_u8(CommandId::WriteNewAppData as u8)?;
+                packer.pack_u32(*offset)?;
+                packer.pack_bytes(data)?;
+            }
             Self::GetValues => {
                 packer.pack_u8(CommandId::GetValues as u8)?;
             }
@@ -176,48 +866,631 @@ impl<'a> Command<'a> {
                 packer.pack_u8(CommandId::SetHandbrake as u8)?;
                 packer.pack_f32(*current, 1000.0)?;
             }
+            Self::SamplePrint(mode, count, decimation) => {
+                packer.pack_u8(CommandId::SamplePrint as u8)?;
+                packer.pack_u8(*mode as u8)?;
+                packer.pack_u16(*count)?;
+                packer.pack_u8(*decimation)?;
+            }
+            Self::DetectMotorParam(current, min_rpm, low_duty) => {
+                packer.pack_u8(CommandId::DetectMotorParam as u8)?;
+                packer.pack_f32(*current, 1000.0)?;
+                packer.pack_f32(*min_rpm, 1000.0)?;
+                packer.pack_f32(*low_duty, 1000.0)?;
+            }
+            Self::DetectMotorRL => {
+                packer.pack_u8(CommandId::DetectMotorRL as u8)?;
+            }
+            Self::DetectMotorFluxLinkage(params) => {
+                packer.pack_u8(CommandId::DetectMotorFluxLinkage as u8)?;
+                packer.pack_f32(params.current, 1000.0)?;
+                packer.pack_f32(params.min_rpm, 1000.0)?;
+                packer.pack_f32(params.duty, 1000.0)?;
+                packer.pack_f32(params.resistance, 1000000.0)?;
+            }
+            Self::DetectEncoder(current) => {
+                packer.pack_u8(CommandId::DetectEncoder as u8)?;
+                packer.pack_f32(*current, 1000.0)?;
+            }
+            Self::DetectHallFoc(current) => {
+                packer.pack_u8(CommandId::DetectHallFoc as u8)?;
+                packer.pack_f32(*current, 1000.0)?;
+            }
+            Self::Reboot => {
+                packer.pack_u8(CommandId::Reboot as u8)?;
+            }
+            Self::GetDecodedPpm => {
+                packer.pack_u8(CommandId::GetDecodedPpm as u8)?;
+            }
+            Self::GetDecodedAdc => {
+                packer.pack_u8(CommandId::GetDecodedAdc as u8)?;
+            }
+            Self::GetDecodedChuk => {
+                packer.pack_u8(CommandId::GetDecodedChuk as u8)?;
+            }
             Self::ForwardCan(controller_id, command) => {
+                if let Self::ForwardCan(..) = command {
+                    return Err(EncodeError::NestedForwardCan);
+                }
                 packer.pack_u8(CommandId::ForwardCan as u8)?;
                 packer.pack_u8(*controller_id)?;
                 command.pack_into(packer)?;
             }
+            Self::SetChuckData(data) => {
+                packer.pack_u8(CommandId::SetChuckData as u8)?;
+                packer.pack_u8(data.js_x)?;
+                packer.pack_u8(data.js_y)?;
+                packer.pack_u8(data.bt_c as u8)?;
+                packer.pack_u8(data.bt_z as u8)?;
+                packer.pack_i16(data.acc_x)?;
+                packer.pack_i16(data.acc_y)?;
+                packer.pack_i16(data.acc_z)?;
+                packer.pack_u8(data.rev_has_state as u8)?;
+                packer.pack_u8(data.is_rev as u8)?;
+            }
+            Self::CustomAppData(data) => {
+                packer.pack_u8(CommandId::CustomAppData as u8)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::GetValuesSetup => {
+                packer.pack_u8(CommandId::GetValuesSetup as u8)?;
+            }
             Self::GetValuesSelective(mask) => {
                 packer.pack_u8(CommandId::GetValuesSelective as u8)?;
                 packer.pack_u32(mask.bits())?;
             }
+            Self::GetValuesSetupSelective(mask) => {
+                packer.pack_u8(CommandId::GetValuesSetupSelective as u8)?;
+                packer.pack_u32(mask.bits())?;
+            }
+            Self::DetectMotorFluxLinkageOpenloop(params) => {
+                packer.pack_u8(CommandId::DetectMotorFluxLinkageOpenloop as u8)?;
+                packer.pack_f32(params.current, 1000.0)?;
+                packer.pack_f32(params.erpm_per_sec, 1000.0)?;
+                packer.pack_f32(params.duty, 1000.0)?;
+                packer.pack_f32(params.resistance, 1000000.0)?;
+                packer.pack_f32(params.inductance, 100000000.0)?;
+            }
+            Self::DetectApplyAllFoc(params) => {
+                packer.pack_u8(CommandId::DetectApplyAllFoc as u8)?;
+                packer.pack_u8(params.detect_can as u8)?;
+                packer.pack_f32(params.max_power_loss, 1000.0)?;
+                packer.pack_f32(params.min_current_in, 1000.0)?;
+                packer.pack_f32(params.max_current_in, 1000.0)?;
+                packer.pack_f32(params.openloop_rpm, 1000.0)?;
+                packer.pack_f32(params.sl_erpm, 1000.0)?;
+            }
+            Self::JumpToBootloaderAllCan => {
+                packer.pack_u8(CommandId::JumpToBootloaderAllCan as u8)?;
+            }
+            Self::EraseNewAppAllCan(size) => {
+                packer.pack_u8(CommandId::EraseNewAppAllCan as u8)?;
+                packer.pack_u32(*size)?;
+            }
+            Self::WriteNewAppDataAllCan(offset, data) => {
+                packer.pack_u8(CommandId::WriteNewAppDataAllCan as u8)?;
+                packer.pack_u32(*offset)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::AppDisableOutput(forward_can, time_ms) => {
+                packer.pack_u8(CommandId::AppDisableOutput as u8)?;
+                packer.pack_u8(*forward_can as u8)?;
+                packer.pack_i32(*time_ms)?;
+            }
+            Self::GetImuData(mask) => {
+                packer.pack_u8(CommandId::GetImuData as u8)?;
+                packer.pack_u16(mask.bits())?;
+            }
+            Self::EraseBootloader => {
+                packer.pack_u8(CommandId::EraseBootloader as u8)?;
+            }
+            Self::EraseBootloaderAllCan => {
+                packer.pack_u8(CommandId::EraseBootloaderAllCan as u8)?;
+            }
+            Self::GetDecodedBalance => {
+                packer.pack_u8(CommandId::GetDecodedBalance as u8)?;
+            }
+            Self::WriteNewAppDataLzo(offset, len, data) => {
+                packer.pack_u8(CommandId::WriteNewAppDataLzo as u8)?;
+                packer.pack_u32(*offset)?;
+                packer.pack_u16(*len)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::WriteNewAppDataAllCanLzo(offset, len, data) => {
+                packer.pack_u8(CommandId::WriteNewAppDataAllCanLzo as u8)?;
+                packer.pack_u32(*offset)?;
+                packer.pack_u16(*len)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::CanFwdFrame(id, is_extended, data) => {
+                let max_id = if *is_extended {
+                    CAN_EXT_ID_MAX
+                } else {
+                    CAN_STD_ID_MAX
+                };
+                if *id > max_id || data.len() > CAN_FRAME_MAX_LEN {
+                    return Err(EncodeError::InvalidCanFrame);
+                }
+                packer.pack_u8(CommandId::CanFwdFrame as u8)?;
+                packer.pack_u32(*id)?;
+                packer.pack_u8(*is_extended as u8)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::SetBatteryCut(params) => {
+                packer.pack_u8(CommandId::SetBatteryCut as u8)?;
+                packer.pack_f32(params.start, 1000.0)?;
+                packer.pack_f32(params.end, 1000.0)?;
+                packer.pack_u8(params.store as u8)?;
+                packer.pack_u8(params.forward_can as u8)?;
+            }
+            Self::SetBleName(name) => {
+                packer.pack_u8(CommandId::SetBleName as u8)?;
+                packer.pack_str(name)?;
+            }
+            Self::SetBlePin(pin) => {
+                packer.pack_u8(CommandId::SetBlePin as u8)?;
+                packer.pack_str(pin)?;
+            }
+            Self::SetCanMode(mode, store, ack) => {
+                packer.pack_u8(CommandId::SetCanMode as u8)?;
+                packer.pack_u8(*store as u8)?;
+                packer.pack_u8(*ack as u8)?;
+                packer.pack_u8(*mode as u8)?;
+            }
+            Self::GetImuCalibration(yaw) => {
+                packer.pack_u8(CommandId::GetImuCalibration as u8)?;
+                packer.pack_f32(*yaw, 1000.0)?;
+            }
+            Self::GetCustomConfigXml(index, offset, len) => {
+                packer.pack_u8(CommandId::GetCustomConfigXml as u8)?;
+                packer.pack_u8(*index)?;
+                packer.pack_u32(*len)?;
+                packer.pack_u32(*offset)?;
+            }
+            Self::GetCustomConfig(index) => {
+                packer.pack_u8(CommandId::GetCustomConfig as u8)?;
+                packer.pack_u8(*index)?;
+            }
+            Self::GetCustomConfigDefault(index) => {
+                packer.pack_u8(CommandId::GetCustomConfigDefault as u8)?;
+                packer.pack_u8(*index)?;
+            }
+            Self::SetCustomConfig(index, values) => {
+                packer.pack_u8(CommandId::SetCustomConfig as u8)?;
+                packer.pack_u8(*index)?;
+                packer.pack_bytes(values)?;
+            }
+            Self::BmsGetValues => {
+                packer.pack_u8(CommandId::BmsGetValues as u8)?;
+            }
+            Self::BmsSetChargeAllowed(allowed) => {
+                packer.pack_u8(CommandId::BmsSetChargeAllowed as u8)?;
+                packer.pack_u8(*allowed as u8)?;
+            }
+            Self::BmsSetBalanceOverride(cell, mode) => {
+                packer.pack_u8(CommandId::BmsSetBalanceOverride as u8)?;
+                packer.pack_u8(*cell)?;
+                packer.pack_u8(*mode)?;
+            }
+            Self::BmsResetCounters(amp_hours, watt_hours) => {
+                packer.pack_u8(CommandId::BmsResetCounters as u8)?;
+                packer.pack_u8(*amp_hours as u8)?;
+                packer.pack_u8(*watt_hours as u8)?;
+            }
+            Self::BmsForceBalance(force) => {
+                packer.pack_u8(CommandId::BmsForceBalance as u8)?;
+                packer.pack_u8(*force as u8)?;
+            }
+            Self::BmsZeroCurrentOffset => {
+                packer.pack_u8(CommandId::BmsZeroCurrentOffset as u8)?;
+            }
+            Self::JumpToBootloaderHw(hw_type) => {
+                packer.pack_u8(CommandId::JumpToBootloaderHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+            }
+            Self::EraseNewAppHw(hw_type, size) => {
+                packer.pack_u8(CommandId::EraseNewAppHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+                packer.pack_u32(*size)?;
+            }
+            Self::WriteNewAppDataHw(hw_type, offset, data) => {
+                packer.pack_u8(CommandId::WriteNewAppDataHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+                packer.pack_u32(*offset)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::EraseBootloaderHw(hw_type) => {
+                packer.pack_u8(CommandId::EraseBootloaderHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+            }
+            Self::JumpToBootloaderAllCanHw(hw_type) => {
+                packer.pack_u8(CommandId::JumpToBootloaderAllCanHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+            }
+            Self::EraseNewAppAllCanHw(hw_type, size) => {
+                packer.pack_u8(CommandId::EraseNewAppAllCanHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+                packer.pack_u32(*size)?;
+            }
+            Self::WriteNewAppDataAllCanHw(hw_type, offset, data) => {
+                packer.pack_u8(CommandId::WriteNewAppDataAllCanHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+                packer.pack_u32(*offset)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::EraseBootloaderAllCanHw(hw_type) => {
+                packer.pack_u8(CommandId::EraseBootloaderAllCanHw as u8)?;
+                packer.pack_u8(*hw_type as u8)?;
+            }
+            Self::SetOdometer(meters) => {
+                packer.pack_u8(CommandId::SetOdometer as u8)?;
+                packer.pack_u32(*meters)?;
+            }
+            Self::GetStats(mask) => {
+                packer.pack_u8(CommandId::GetStats as u8)?;
+                packer.pack_u16(mask.bits())?;
+            }
+            Self::ResetStats(ack) => {
+                packer.pack_u8(CommandId::ResetStats as u8)?;
+                packer.pack_u8(*ack as u8)?;
+            }
+            Self::LispReadCode(offset, len) => {
+                packer.pack_u8(CommandId::LispReadCode as u8)?;
+                packer.pack_u32(*len)?;
+                packer.pack_u32(*offset)?;
+            }
+            Self::LispWriteCode(offset, data) => {
+                packer.pack_u8(CommandId::LispWriteCode as u8)?;
+                packer.pack_u32(*offset)?;
+                packer.pack_bytes(data)?;
+            }
+            Self::LispEraseCode(size) => {
+                packer.pack_u8(CommandId::LispEraseCode as u8)?;
+                packer.pack_i32(*size)?;
+            }
+            Self::LispSetRunning(running) => {
+                packer.pack_u8(CommandId::LispSetRunning as u8)?;
+                packer.pack_u8(*running as u8)?;
+            }
+            Self::LispGetStats(all) => {
+                packer.pack_u8(CommandId::LispGetStats as u8)?;
+                packer.pack_u8(*all as u8)?;
+            }
+            Self::LispReplCmd(expression) => {
+                packer.pack_u8(CommandId::LispReplCmd as u8)?;
+                packer.pack_str(expression)?;
+            }
+            Self::FileList(path, from) => {
+                packer.pack_u8(CommandId::FileList as u8)?;
+                packer.pack_str(path)?;
+                packer.pack_str(from)?;
+            }
+            Self::FileRead(path, offset) => {
+                packer.pack_u8(CommandId::FileRead as u8)?;
+                packer.pack_str(path)?;
+                packer.pack_u32(*offset)?;
+            }
+            Self::FileWrite(params) => {
+                packer.pack_u8(CommandId::FileWrite as u8)?;
+                packer.pack_str(params.path)?;
+                packer.pack_u32(params.offset)?;
+                packer.pack_u32(params.size)?;
+                packer.pack_bytes(params.data)?;
+            }
+            Self::FileMkdir(path) => {
+                packer.pack_u8(CommandId::FileMkdir as u8)?;
+                packer.pack_str(path)?;
+            }
+            Self::FileRemove(path) => {
+                packer.pack_u8(CommandId::FileRemove as u8)?;
+                packer.pack_str(path)?;
+            }
+            Self::LogStart(params) => {
+                packer.pack_u8(CommandId::LogStart as u8)?;
+                packer.pack_u16(params.field_count)?;
+                packer.pack_f32_auto(params.rate_hz)?;
+                packer.pack_u8(params.append_time as u8)?;
+                packer.pack_u8(params.append_gnss as u8)?;
+                packer.pack_u8(params.append_gnss_time as u8)?;
+            }
+            Self::LogStop => {
+                packer.pack_u8(CommandId::LogStop as u8)?;
+            }
+            Self::LogConfigField(field) => {
+                packer.pack_u8(CommandId::LogConfigField as u8)?;
+                packer.pack_u16(field.index)?;
+                packer.pack_str(field.key)?;
+                packer.pack_str(field.name)?;
+                packer.pack_str(field.unit)?;
+                packer.pack_u8(field.precision)?;
+                packer.pack_u8(field.is_relative as u8)?;
+                packer.pack_u8(field.is_timestamp as u8)?;
+            }
+            Self::GetGnss(mask) => {
+                packer.pack_u8(CommandId::GetGnss as u8)?;
+                packer.pack_u16(mask.bits())?;
+            }
+            Self::Shutdown => {
+                packer.pack_u8(CommandId::Shutdown as u8)?;
+            }
+            Self::CanUpdateBaudAll(baud, delay) => {
+                packer.pack_u8(CommandId::CanUpdateBaudAll as u8)?;
+                packer.pack_u16(baud.kbits())?;
+                packer.pack_u16(*delay)?;
+            }
         }
         Ok(())
     }
 }
 
-/// Indicates specific error conditions or hardware failures.
-///
-/// Fault codes are typically retrieved as part of the [`Values`] struct when
-/// calling [`Command::GetValues`] or [`Command::GetValuesSelective`].
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Parameters of [`Command::DetectMotorFluxLinkage`].
+#[derive(Debug, Copy, Clone, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[repr(u8)]
-pub enum FaultCode {
-    #[default]
-    None = 0,
-    OverVoltage,
-    UnderVoltage,
-    Drv,
-    AbsOverCurrent,
-    OverTempFet,
-    OverTempMotor,
-    GateDriverOverVoltage,
-    GateDriverUnderVoltage,
-    McuUnderVoltage,
-    BootingFromWatchdogReset,
-    EncoderSpi,
-    EncoderSinCosBelowMinAmplitude,
-    EncoderSinCosAboveMaxAmplitude,
-    FlashCorruption,
-    HighOffsetCurrentSensor1,
-    HighOffsetCurrentSensor2,
-    HighOffsetCurrentSensor3,
-    UnbalancedCurrents,
+pub struct FluxLinkageParams {
+    /// Detection current in amperes.
+    pub current: f32,
+    /// Minimum RPM to run the detection at.
+    pub min_rpm: f32,
+    /// Duty cycle to run the detection at.
+    pub duty: f32,
+    /// Motor resistance in ohms.
+    pub resistance: f32,
+}
+
+/// Parameters of [`Command::DetectMotorFluxLinkageOpenloop`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FluxLinkageOpenloopParams {
+    /// Detection current in amperes.
+    pub current: f32,
+    /// Open loop acceleration in ERPM per second.
+    pub erpm_per_sec: f32,
+    /// Duty cycle to run the detection at.
+    pub duty: f32,
+    /// Motor resistance in ohms.
+    pub resistance: f32,
+    /// Motor inductance in henries.
+    pub inductance: f32,
+}
+
+/// Parameters of [`Command::DetectApplyAllFoc`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApplyAllFocParams {
+    /// Also run the detection on every controller on the CAN bus.
+    pub detect_can: bool,
+    /// Maximum power loss of the motor in watts, used to derive the current
+    /// limits.
+    pub max_power_loss: f32,
+    /// Minimum input current in amperes. Not applied if `0.0`.
+    pub min_current_in: f32,
+    /// Maximum input current in amperes. Not applied if `0.0`.
+    pub max_current_in: f32,
+    /// Open loop RPM. Derived from the motor parameters if `0.0`.
+    pub openloop_rpm: f32,
+    /// Sensorless ERPM. Derived from the motor parameters if `0.0`.
+    pub sl_erpm: f32,
+}
+
+/// Remote control input of [`Command::SetChuckData`], laid out like the data of
+/// a Wii Nunchuk.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChuckData {
+    /// Joystick X position, `128` being the center.
+    pub js_x: u8,
+    /// Joystick Y position, `128` being the center.
+    pub js_y: u8,
+    /// Whether the C button is pressed.
+    pub bt_c: bool,
+    /// Whether the Z button is pressed.
+    pub bt_z: bool,
+    /// Raw accelerometer reading along the X axis.
+    pub acc_x: i16,
+    /// Raw accelerometer reading along the Y axis.
+    pub acc_y: i16,
+    /// Raw accelerometer reading along the Z axis.
+    pub acc_z: i16,
+    /// Whether the remote tracks the reverse state itself, in which case
+    /// `is_rev` is used instead of the button based reverse toggling.
+    pub rev_has_state: bool,
+    /// Whether reverse is engaged.
+    pub is_rev: bool,
+}
+
+/// Parameters of [`Command::SetBatteryCut`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BatteryCutParams {
+    /// Voltage at which the input current limiting starts.
+    pub start: f32,
+    /// Voltage at which the input current is limited to zero.
+    pub end: f32,
+    /// Store the voltages in the configuration, so that they persist across
+    /// reboots.
+    pub store: bool,
+    /// Also apply the voltages to every controller on the CAN bus.
+    pub forward_can: bool,
+}
+
+/// Parameters of [`Command::FileWrite`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FileWriteParams<'a> {
+    /// Path of the file.
+    pub path: &'a str,
+    /// Offset of the chunk within the file.
+    pub offset: u32,
+    /// Total size of the file.
+    pub size: u32,
+    /// The chunk itself.
+    pub data: &'a [u8],
+}
+
+/// Parameters of [`Command::LogStart`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LogStartParams {
+    /// Number of configured fields to log.
+    pub field_count: u16,
+    /// Rate at which samples are written, in hertz.
+    pub rate_hz: f32,
+    /// Prepend a timestamp to every sample.
+    pub append_time: bool,
+    /// Append the GNSS position to every sample.
+    pub append_gnss: bool,
+    /// Append the GNSS time to every sample.
+    pub append_gnss_time: bool,
+}
+
+/// A log field configured with [`Command::LogConfigField`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LogField<'a> {
+    /// Index of the field within a sample.
+    pub index: u16,
+    /// Unique key of the field.
+    pub key: &'a str,
+    /// Human-readable name of the field.
+    pub name: &'a str,
+    /// Unit of the field.
+    pub unit: &'a str,
+    /// Number of decimals to display.
+    pub precision: u8,
+    /// The values are relative to the first logged value.
+    pub is_relative: bool,
+    /// The values are timestamps.
+    pub is_timestamp: bool,
+}
+
+/// Hardware types targeted by the `*Hw` bootloader commands, e.g.
+/// [`Command::EraseNewAppHw`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum HwType {
+    /// Motor controllers.
+    #[default]
+    Vesc = 0,
+    /// Battery management systems.
+    VescBms,
+    /// Custom modules, e.g. the VESC Express.
+    CustomModule,
+}
+
+/// CAN modes, set with [`Command::SetCanMode`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum CanMode {
+    /// The VESC protocol.
+    #[default]
+    Vesc = 0,
+    /// The UAVCAN protocol.
+    Uavcan,
+    /// Bridges CAN frames to the communication interfaces.
+    CommBridge,
+    /// CAN is not used.
+    Unused,
+}
+
+/// CAN baud rates supported by the firmware, e.g. for
+/// [`Command::CanUpdateBaudAll`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum CanBaud {
+    Kbits125 = 0,
+    Kbits250,
+    #[default]
+    Kbits500,
+    Mbits1,
+    Kbits10,
+    Kbits20,
+    Kbits50,
+    Kbits75,
+    Kbits100,
+}
+
+impl CanBaud {
+    /// Returns the baud rate in kbit/s.
+    pub fn kbits(self) -> u16 {
+        match self {
+            CanBaud::Kbits125 => 125,
+            CanBaud::Kbits250 => 250,
+            CanBaud::Kbits500 => 500,
+            CanBaud::Mbits1 => 1000,
+            CanBaud::Kbits10 => 10,
+            CanBaud::Kbits20 => 20,
+            CanBaud::Kbits50 => 50,
+            CanBaud::Kbits75 => 75,
+            CanBaud::Kbits100 => 100,
+        }
+    }
+}
+
+/// Sampling modes of [`Command::SamplePrint`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum SamplingMode {
+    /// Stops sampling.
+    #[default]
+    Off = 0,
+    /// Samples right away.
+    Now,
+    /// Samples once the motor is started.
+    Start,
+    /// Samples around the motor start, keeping samples from before it.
+    TriggerStart,
+    /// Samples around the next fault, keeping samples from before it.
+    TriggerFault,
+    /// Like [`TriggerStart`], but keeps the samples until
+    /// [`SendLastSamples`] is requested.
+    ///
+    /// [`TriggerStart`]: Self::TriggerStart
+    /// [`SendLastSamples`]: Self::SendLastSamples
+    TriggerStartNoSend,
+    /// Like [`TriggerFault`], but keeps the samples until
+    /// [`SendLastSamples`] is requested.
+    ///
+    /// [`TriggerFault`]: Self::TriggerFault
+    /// [`SendLastSamples`]: Self::SendLastSamples
+    TriggerFaultNoSend,
+    /// Sends the samples kept by a previous sampling.
+    SendLastSamples,
+    /// Sends a single sample.
+    SendSingleSample,
+}
+
+/// Indicates specific error conditions or hardware failures.
+///
+/// Fault codes are typically retrieved as part of the [`Values`] struct when
+/// calling [`Command::GetValues`] or [`Command::GetValuesSelective`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum FaultCode {
+    #[default]
+    None = 0,
+    OverVoltage,
+    UnderVoltage,
+    Drv,
+    AbsOverCurrent,
+    OverTempFet,
+    OverTempMotor,
+    GateDriverOverVoltage,
+    GateDriverUnderVoltage,
+    McuUnderVoltage,
+    BootingFromWatchdogReset,
+    EncoderSpi,
+    EncoderSinCosBelowMinAmplitude,
+    EncoderSinCosAboveMaxAmplitude,
+    FlashCorruption,
+    HighOffsetCurrentSensor1,
+    HighOffsetCurrentSensor2,
+    HighOffsetCurrentSensor3,
+    UnbalancedCurrents,
     Brk,
     ResolverLot,
     ResolverDos,
@@ -312,68 +1585,1360 @@ impl From<u8> for FaultCode {
     }
 }
 
-/// Telemetry data returned by the motor controller.
-///
-/// Contains temperatures, currents, voltages, rpm, and so on. Returned by
-/// [`Command::GetValues`] or [`Command::GetValuesSelective`].
-///
-/// With [`Command::GetValuesSelective`], only the fields specified by the
-/// [`ValuesMask`] are populated; all others remain at their default.
-#[derive(Debug, Copy, Clone, Default)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Values {
-    pub temp_mosfet: f32,
-    pub temp_motor: f32,
-    pub avg_current_motor: f32,
-    pub avg_current_input: f32,
-    pub avg_current_d: f32,
-    pub avg_current_q: f32,
-    pub duty_cycle: f32,
-    pub rpm: f32,
-    pub voltage_in: f32,
-    pub amp_hours: f32,
-    pub amp_hours_charged: f32,
-    pub watt_hours: f32,
-    pub watt_hours_charged: f32,
-    pub tachometer: i32,
-    pub tachometer_abs: i32,
-    pub fault_code: FaultCode,
-    pub pid_pos: f32,
-    pub controller_id: u8,
-    pub temp_mosfet1: f32,
-    pub temp_mosfet2: f32,
-    pub temp_mosfet3: f32,
-    pub avg_voltage_d: f32,
-    pub avg_voltage_q: f32,
-    pub status: u8,
+/// Telemetry data returned by the motor controller.
+///
+/// Contains temperatures, currents, voltages, rpm, and so on. Returned by
+/// [`Command::GetValues`] or [`Command::GetValuesSelective`].
+///
+/// With [`Command::GetValuesSelective`], only the fields specified by the
+/// [`ValuesMask`] are populated; all others remain at their default.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Values {
+    pub temp_mosfet: f32,
+    pub temp_motor: f32,
+    pub avg_current_motor: f32,
+    pub avg_current_input: f32,
+    pub avg_current_d: f32,
+    pub avg_current_q: f32,
+    pub duty_cycle: f32,
+    pub rpm: f32,
+    pub voltage_in: f32,
+    pub amp_hours: f32,
+    pub amp_hours_charged: f32,
+    pub watt_hours: f32,
+    pub watt_hours_charged: f32,
+    pub tachometer: i32,
+    pub tachometer_abs: i32,
+    pub fault_code: FaultCode,
+    pub pid_pos: f32,
+    pub controller_id: u8,
+    pub temp_mosfet1: f32,
+    pub temp_mosfet2: f32,
+    pub temp_mosfet3: f32,
+    pub avg_voltage_d: f32,
+    pub avg_voltage_q: f32,
+    pub status: u8,
+}
+
+/// Setup telemetry data returned by the motor controller.
+///
+/// Unlike [`Values`], currents, energy and battery figures are aggregated over
+/// every controller on the CAN bus, and speed and distance are derived from
+/// the configured wheel diameter and gearing. Returned by
+/// [`Command::GetValuesSetup`] or [`Command::GetValuesSetupSelective`].
+///
+/// With [`Command::GetValuesSetupSelective`], only the fields specified by the
+/// [`SetupValuesMask`] are populated; all others remain at their default.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SetupValues {
+    pub temp_mosfet: f32,
+    pub temp_motor: f32,
+    /// Total motor current of all controllers in amperes.
+    pub current_motor: f32,
+    /// Total input current of all controllers in amperes.
+    pub current_input: f32,
+    pub duty_cycle: f32,
+    pub rpm: f32,
+    /// Speed in meters per second.
+    pub speed: f32,
+    pub voltage_in: f32,
+    /// Battery level in the range from `0.0` to `1.0`.
+    pub battery_level: f32,
+    pub amp_hours: f32,
+    pub amp_hours_charged: f32,
+    pub watt_hours: f32,
+    pub watt_hours_charged: f32,
+    /// Traveled distance in meters.
+    pub distance: f32,
+    /// Absolute traveled distance in meters.
+    pub distance_abs: f32,
+    pub pid_pos: f32,
+    pub fault_code: FaultCode,
+    pub controller_id: u8,
+    /// Number of controllers on the CAN bus, including this one.
+    pub num_vescs: u8,
+    /// Remaining battery energy in watt-hours.
+    pub battery_watt_hours: f32,
+    /// Odometer in meters.
+    pub odometer: u32,
+    /// Time since the controller booted in milliseconds.
+    pub uptime_ms: u32,
+}
+
+/// Statistics returned by the motor controller in response to
+/// [`Command::GetStats`].
+///
+/// Only the fields specified by the [`StatsMask`] are populated; all others
+/// remain at their default.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StatsValues {
+    /// Average speed in meters per second.
+    pub speed_avg: f32,
+    /// Maximum speed in meters per second.
+    pub speed_max: f32,
+    /// Average power in watts.
+    pub power_avg: f32,
+    /// Maximum power in watts.
+    pub power_max: f32,
+    /// Average motor current in amperes.
+    pub current_avg: f32,
+    /// Maximum motor current in amperes.
+    pub current_max: f32,
+    /// Average MOSFET temperature in degrees Celsius.
+    pub temp_mosfet_avg: f32,
+    /// Maximum MOSFET temperature in degrees Celsius.
+    pub temp_mosfet_max: f32,
+    /// Average motor temperature in degrees Celsius.
+    pub temp_motor_avg: f32,
+    /// Maximum motor temperature in degrees Celsius.
+    pub temp_motor_max: f32,
+    /// Time the statistics have been collected for in seconds.
+    pub count_time: f32,
+}
+
+impl StatsValues {
+    /// Returns the distance travelled in meters, derived from the average
+    /// speed and the collection time. Requires [`StatsMask::SPEED_AVG`] and
+    /// [`StatsMask::COUNT_TIME`].
+    pub fn distance(&self) -> f32 {
+        self.speed_avg * self.count_time
+    }
+}
+
+/// GNSS data returned by the motor controller in response to
+/// [`Command::GetGnss`].
+///
+/// Only the fields specified by the [`GnssMask`] are populated; all others
+/// remain at their default.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GnssData {
+    /// Latitude in degrees.
+    pub latitude: f64,
+    /// Longitude in degrees.
+    pub longitude: f64,
+    /// Height above sea level in meters.
+    pub height: f32,
+    /// Ground speed in meters per second.
+    pub speed: f32,
+    /// Horizontal dilution of precision.
+    pub hdop: f32,
+    /// Time of the fix in milliseconds since midnight UTC.
+    pub ms_today: i32,
+    /// Year of the fix.
+    pub year: i16,
+    /// Month of the fix.
+    pub month: i8,
+    /// Day of the month of the fix.
+    pub day: i8,
+    /// Time since the last position update in seconds.
+    pub age: f32,
+}
+
+/// IMU data returned by the motor controller in response to
+/// [`Command::GetImuData`].
+///
+/// Only the fields specified by the [`ImuMask`] are populated; all others
+/// remain at their default.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ImuData {
+    /// Roll angle in radians.
+    pub roll: f32,
+    /// Pitch angle in radians.
+    pub pitch: f32,
+    /// Yaw angle in radians.
+    pub yaw: f32,
+    /// Acceleration along the X axis in g.
+    pub acc_x: f32,
+    /// Acceleration along the Y axis in g.
+    pub acc_y: f32,
+    /// Acceleration along the Z axis in g.
+    pub acc_z: f32,
+    /// Angular rate around the X axis in degrees per second.
+    pub gyro_x: f32,
+    /// Angular rate around the Y axis in degrees per second.
+    pub gyro_y: f32,
+    /// Angular rate around the Z axis in degrees per second.
+    pub gyro_z: f32,
+    /// Magnetic field along the X axis.
+    pub mag_x: f32,
+    /// Magnetic field along the Y axis.
+    pub mag_y: f32,
+    /// Magnetic field along the Z axis.
+    pub mag_z: f32,
+    /// Scalar component of the orientation quaternion.
+    pub q0: f32,
+    /// X component of the orientation quaternion.
+    pub q1: f32,
+    /// Y component of the orientation quaternion.
+    pub q2: f32,
+    /// Z component of the orientation quaternion.
+    pub q3: f32,
+}
+
+/// IMU calibration returned by the motor controller in response to
+/// [`Command::GetImuCalibration`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ImuCalibration {
+    /// Roll mounting angle in degrees.
+    pub roll: f32,
+    /// Pitch mounting angle in degrees.
+    pub pitch: f32,
+    /// Yaw mounting angle in degrees.
+    pub yaw: f32,
+    /// Accelerometer offset along the X axis in g.
+    pub acc_offset_x: f32,
+    /// Accelerometer offset along the Y axis in g.
+    pub acc_offset_y: f32,
+    /// Accelerometer offset along the Z axis in g.
+    pub acc_offset_z: f32,
+    /// Gyroscope offset around the X axis in degrees per second.
+    pub gyro_offset_x: f32,
+    /// Gyroscope offset around the Y axis in degrees per second.
+    pub gyro_offset_y: f32,
+    /// Gyroscope offset around the Z axis in degrees per second.
+    pub gyro_offset_z: f32,
+}
+
+/// Input decoded by the PPM app, returned in response to
+/// [`Command::GetDecodedPpm`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DecodedPpm {
+    /// Decoded input level, ranging from `-1.0` to `1.0`.
+    pub value: f32,
+    /// Length of the last received pulse in milliseconds.
+    pub pulse_len: f32,
+}
+
+/// Input decoded by the ADC app, returned in response to
+/// [`Command::GetDecodedAdc`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DecodedAdc {
+    /// Decoded level of the first channel.
+    pub value: f32,
+    /// Raw voltage of the first channel.
+    pub voltage: f32,
+    /// Decoded level of the second channel.
+    pub value2: f32,
+    /// Raw voltage of the second channel.
+    pub voltage2: f32,
+}
+
+/// State of the balance app, returned in response to
+/// [`Command::GetDecodedBalance`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DecodedBalance {
+    /// Output of the balance PID controller.
+    pub pid_output: f32,
+    /// Pitch angle in degrees.
+    pub pitch: f32,
+    /// Roll angle in degrees.
+    pub roll: f32,
+    /// Duration of the last control loop iteration in microseconds.
+    pub loop_time_us: u32,
+    /// Motor current in amperes.
+    pub motor_current: f32,
+    /// State of the balance app.
+    pub state: u16,
+    /// State of the footpad switch.
+    pub switch_state: u16,
+    /// Voltage of the first footpad sensor ADC.
+    pub adc1: f32,
+    /// Voltage of the second footpad sensor ADC.
+    pub adc2: f32,
+    /// First debug value. Older firmware does not report the debug values, in
+    /// which case they are `0.0`.
+    pub debug1: f32,
+    /// Second debug value.
+    pub debug2: f32,
+}
+
+/// Result of the BLDC motor parameter detection, returned in response to
+/// [`Command::DetectMotorParam`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BldcDetection {
+    pub cycle_int_limit: f32,
+    pub coupling_k: f32,
+    /// Detected hall sensor table. Invalid entries are `255`.
+    pub hall_table: [u8; 8],
+    /// Hall sensor detection result; `0` on success.
+    pub hall_res: i8,
+}
+
+/// Motor resistance and inductance, returned in response to
+/// [`Command::DetectMotorRL`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ResistanceInductance {
+    /// Resistance in ohms.
+    pub resistance: f32,
+    /// Inductance in microhenries.
+    pub inductance: f32,
+    /// Difference between the D and Q axis inductances in microhenries. Older
+    /// firmware does not report it, in which case it is `0.0`.
+    pub ld_lq_diff: f32,
+}
+
+/// Result of the open loop flux linkage detection, returned in response to
+/// [`Command::DetectMotorFluxLinkageOpenloop`].
+///
+/// Older firmware reports only the flux linkage, in which case the encoder
+/// fields are left at their default.
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FluxLinkageDetection {
+    /// Flux linkage in webers.
+    pub flux_linkage: f32,
+    /// Encoder offset in degrees, detected while the motor was spinning.
+    pub encoder_offset: f32,
+    /// Ratio between electrical and mechanical revolutions of the encoder.
+    pub encoder_ratio: f32,
+    /// Whether the encoder direction is inverted.
+    pub encoder_inverted: bool,
+}
+
+/// Result of the encoder detection, returned in response to
+/// [`Command::DetectEncoder`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EncoderDetection {
+    /// Encoder offset in degrees.
+    pub offset: f32,
+    /// Ratio between electrical and mechanical revolutions.
+    pub ratio: f32,
+    /// Whether the encoder direction is inverted.
+    pub inverted: bool,
+}
+
+/// Result of the hall sensor detection, returned in response to
+/// [`Command::DetectHallFoc`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HallDetection {
+    /// Detected hall sensor table, mapping each hall state to an angle.
+    pub hall_table: [u8; 8],
+    /// Whether the detection succeeded.
+    pub success: bool,
+}
+
+/// Variable length data of a [`CommandReply`], stored in a buffer of `N`
+/// bytes. Dereferences to the received bytes.
+///
+/// The capacity matches the buffer length of the [`Decoder`] the reply is
+/// decoded by, which no data received in a single frame can exceed.
+///
+/// [`Decoder`]: crate::Decoder
+#[derive(Copy, Clone)]
+pub struct ReplyData<const N: usize = DEFAULT_BUFFER_LEN> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ReplyData<N> {
+    /// Copies `data` into a new buffer. Fails with
+    /// [`DecodeError::CapacityExceeded`] if `data` is longer than `N`.
+    pub fn new(data: &[u8]) -> Result<Self, DecodeError> {
+        let mut buf = [0u8; N];
+        buf.get_mut(..data.len())
+            .ok_or(DecodeError::CapacityExceeded)?
+            .copy_from_slice(data);
+        Ok(Self {
+            buf,
+            len: data.len(),
+        })
+    }
+
+    /// Returns the received bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Returns the received bytes as a string, if they are valid UTF-8.
+    pub fn as_str(&self) -> Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(self.as_slice())
+    }
+}
+
+impl<const N: usize> Default for ReplyData<N> {
+    fn default() -> Self {
+        Self {
+            buf: [0u8; N],
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> Deref for ReplyData<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> PartialEq for ReplyData<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<const N: usize> Eq for ReplyData<N> {}
+
+impl<const N: usize> fmt::Debug for ReplyData<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for ReplyData<N> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[u8]}", self.as_slice())
+    }
+}
+
+/// A sample of phase currents and voltages, sent in response to
+/// [`Command::SamplePrint`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MotorSample {
+    /// Current of the first phase in amperes.
+    pub current1: f32,
+    /// Current of the second phase in amperes.
+    pub current2: f32,
+    /// Voltage of the first phase in volts.
+    pub voltage1: f32,
+    /// Voltage of the second phase in volts.
+    pub voltage2: f32,
+    /// Voltage of the third phase in volts.
+    pub voltage3: f32,
+    /// Virtual zero voltage in volts.
+    pub voltage_zero: f32,
+    /// Filtered total current in amperes.
+    pub current_total: f32,
+    /// Switching frequency in hertz.
+    pub switching_frequency: f32,
+    /// Internal status bits of the motor controller.
+    pub status: u8,
+    /// Commutation phase, only meaningful for BLDC.
+    pub phase: u8,
+}
+
+/// Largest standard (11-bit) CAN ID.
+const CAN_STD_ID_MAX: u32 = 0x7FF;
+
+/// Largest extended (29-bit) CAN ID.
+const CAN_EXT_ID_MAX: u32 = 0x1FFF_FFFF;
+
+/// Maximum number of data bytes of a CAN frame.
+const CAN_FRAME_MAX_LEN: usize = 8;
+
+/// A raw CAN frame received by the controller, relayed as
+/// [`CommandReply::CanFwdFrame`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CanFrame {
+    /// The CAN ID.
+    pub id: u32,
+    /// `true` if the ID is extended (29 bits) rather than standard (11 bits).
+    pub is_extended: bool,
+    data: [u8; CAN_FRAME_MAX_LEN],
+    len: usize,
+}
+
+impl CanFrame {
+    /// Returns the data bytes of the frame.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+/// Maximum number of samples reported in [`ExperimentSamples`], as many as fit
+/// into a reply of the firmware.
+pub const EXPERIMENT_MAX_SAMPLES: usize = 63;
+
+/// Values sampled by a firmware experiment, sent as
+/// [`CommandReply::ExperimentSample`].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ExperimentSamples {
+    samples: [f32; EXPERIMENT_MAX_SAMPLES],
+    len: usize,
+}
+
+impl Default for ExperimentSamples {
+    fn default() -> Self {
+        Self {
+            samples: [0.0; EXPERIMENT_MAX_SAMPLES],
+            len: 0,
+        }
+    }
+}
+
+impl ExperimentSamples {
+    /// Returns the sampled values.
+    pub fn samples(&self) -> &[f32] {
+        &self.samples[..self.len]
+    }
+}
+
+/// Maximum number of cells reported in [`BmsValues`].
+pub const BMS_MAX_CELLS: usize = 50;
+
+/// Maximum number of temperature sensors reported in [`BmsValues`].
+pub const BMS_MAX_TEMPS: usize = 50;
+
+/// Values of the BMS, returned in response to [`Command::BmsGetValues`].
+///
+/// The cell and temperature readings are stored in arrays with a fixed
+/// capacity. Use [`cell_voltages`], [`cell_balancing`] and [`temps`] to access
+/// the reported entries.
+///
+/// [`cell_voltages`]: Self::cell_voltages
+/// [`cell_balancing`]: Self::cell_balancing
+/// [`temps`]: Self::temps
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BmsValues {
+    /// Total pack voltage in volts.
+    pub voltage: f32,
+    /// Voltage at the charge input in volts.
+    pub charge_voltage: f32,
+    /// Pack current in amperes.
+    pub current: f32,
+    /// Pack current measured by the monitoring IC in amperes.
+    pub current_ic: f32,
+    /// Consumed amp hours.
+    pub amp_hours: f32,
+    /// Consumed watt hours.
+    pub watt_hours: f32,
+    cell_count: usize,
+    cell_voltage: [f32; BMS_MAX_CELLS],
+    cell_balancing_state: [bool; BMS_MAX_CELLS],
+    temp_count: usize,
+    temp: [f32; BMS_MAX_TEMPS],
+    /// Temperature of the monitoring IC in degrees Celsius.
+    pub temp_ic: f32,
+    /// Temperature of the humidity sensor in degrees Celsius.
+    pub temp_humidity_sensor: f32,
+    /// Relative humidity in percent.
+    pub humidity: f32,
+    /// Highest cell temperature in degrees Celsius.
+    pub temp_cell_max: f32,
+    /// State of charge, ranging from `0.0` to `1.0`.
+    pub soc: f32,
+    /// State of health, ranging from `0.0` to `1.0`.
+    pub soh: f32,
+    /// CAN ID of the BMS.
+    pub can_id: u8,
+    /// Total amp hours charged over the lifetime of the BMS. Older firmware
+    /// does not report the lifetime counters, in which case they are `0.0`.
+    pub amp_hours_charged_total: f32,
+    /// Total watt hours charged over the lifetime of the BMS.
+    pub watt_hours_charged_total: f32,
+    /// Total amp hours discharged over the lifetime of the BMS.
+    pub amp_hours_discharged_total: f32,
+    /// Total watt hours discharged over the lifetime of the BMS.
+    pub watt_hours_discharged_total: f32,
+    /// Pressure in pascals. Older firmware does not report it, in which case
+    /// it is `0.0`.
+    pub pressure: f32,
+}
+
+impl BmsValues {
+    /// Returns the number of cells.
+    pub fn cell_count(&self) -> usize {
+        self.cell_count
+    }
+
+    /// Returns the number of temperature sensors.
+    pub fn temp_count(&self) -> usize {
+        self.temp_count
+    }
+
+    /// Returns the voltages of the reported cells in volts.
+    pub fn cell_voltages(&self) -> &[f32] {
+        &self.cell_voltage[..self.cell_count]
+    }
+
+    /// Returns the balancing states of the reported cells.
+    pub fn cell_balancing(&self) -> &[bool] {
+        &self.cell_balancing_state[..self.cell_count]
+    }
+
+    /// Returns the readings of the reported temperature sensors in degrees
+    /// Celsius.
+    pub fn temps(&self) -> &[f32] {
+        &self.temp[..self.temp_count]
+    }
+}
+
+impl Default for BmsValues {
+    fn default() -> Self {
+        Self {
+            voltage: 0.0,
+            charge_voltage: 0.0,
+            current: 0.0,
+            current_ic: 0.0,
+            amp_hours: 0.0,
+            watt_hours: 0.0,
+            cell_count: 0,
+            cell_voltage: [0.0; BMS_MAX_CELLS],
+            cell_balancing_state: [false; BMS_MAX_CELLS],
+            temp_count: 0,
+            temp: [0.0; BMS_MAX_TEMPS],
+            temp_ic: 0.0,
+            temp_humidity_sensor: 0.0,
+            humidity: 0.0,
+            temp_cell_max: 0.0,
+            soc: 0.0,
+            soh: 0.0,
+            can_id: 0,
+            amp_hours_charged_total: 0.0,
+            watt_hours_charged_total: 0.0,
+            amp_hours_discharged_total: 0.0,
+            watt_hours_discharged_total: 0.0,
+            pressure: 0.0,
+        }
+    }
+}
+
+/// A chunk of the XML describing a custom config, returned in response to
+/// [`Command::GetCustomConfigXml`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigXmlChunk<const N: usize = DEFAULT_BUFFER_LEN> {
+    /// Index of the custom config.
+    pub index: u8,
+    /// Total length of the XML.
+    pub total_len: u32,
+    /// Offset of the chunk within the XML.
+    pub offset: u32,
+    /// The chunk itself.
+    pub data: ReplyData<N>,
+}
+
+/// Axis names of a plot, sent as [`CommandReply::PlotInit`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PlotAxisNames<const N: usize = DEFAULT_BUFFER_LEN> {
+    /// Both names, each followed by a null terminator.
+    data: ReplyData<N>,
+    x_len: usize,
+    y_len: usize,
+}
+
+impl<const N: usize> PlotAxisNames<N> {
+    /// Returns the name of the x axis.
+    pub fn x(&self) -> &str {
+        // The strings are validated while decoding.
+        let text = self.data.get(..self.x_len);
+        core::str::from_utf8(text.unwrap_or_default()).unwrap_or_default()
+    }
+
+    /// Returns the name of the y axis.
+    pub fn y(&self) -> &str {
+        let start = self.x_len + 1;
+        let text = self.data.get(start..start + self.y_len);
+        core::str::from_utf8(text.unwrap_or_default()).unwrap_or_default()
+    }
+}
+
+/// LispBM runtime statistics, returned in response to
+/// [`Command::LispGetStats`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LispStats<const N: usize = DEFAULT_BUFFER_LEN> {
+    /// CPU usage in percent.
+    pub cpu_use: f32,
+    /// Heap usage in percent.
+    pub heap_use: f32,
+    /// Memory usage in percent.
+    pub mem_use: f32,
+    /// Stack usage in percent.
+    pub stack_use: f32,
+    /// The result of the last finished context, followed by the bindings.
+    data: ReplyData<N>,
+    bindings_pos: usize,
+}
+
+impl<const N: usize> LispStats<N> {
+    /// Returns the printed result of the last finished context.
+    pub fn done_ctx_r(&self) -> &str {
+        // The strings are validated while decoding.
+        let text = self.data.get(..self.bindings_pos.saturating_sub(1));
+        core::str::from_utf8(text.unwrap_or_default()).unwrap_or_default()
+    }
+
+    /// Returns an iterator over the global bindings and their values.
+    pub fn bindings(&self) -> LispBindings<'_> {
+        LispBindings {
+            unpacker: Unpacker::new(self.data.get(self.bindings_pos..).unwrap_or_default()),
+        }
+    }
+}
+
+/// Iterator over the global bindings of [`LispStats`], yielding the name and
+/// value of each binding.
+pub struct LispBindings<'a> {
+    unpacker: Unpacker<'a>,
+}
+
+impl<'a> Iterator for LispBindings<'a> {
+    type Item = (&'a str, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.unpacker.has_remaining() {
+            return None;
+        }
+        let name = self.unpacker.unpack_str().ok()?;
+        let value = self.unpacker.unpack_f32_auto().ok()?;
+        Some((name, value))
+    }
+}
+
+/// A page of directory entries, returned in response to
+/// [`Command::FileList`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FileList<const N: usize = DEFAULT_BUFFER_LEN> {
+    /// `true` if more entries are left. Request them by passing the name of
+    /// the last entry to [`Command::FileList`].
+    pub has_more: bool,
+    data: ReplyData<N>,
+}
+
+impl<const N: usize> FileList<N> {
+    /// Returns an iterator over the entries of the page.
+    pub fn entries(&self) -> FileEntries<'_> {
+        FileEntries {
+            unpacker: Unpacker::new(self.data.as_slice()),
+        }
+    }
+}
+
+/// An entry of a [`FileList`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FileEntry<'a> {
+    /// Name of the entry.
+    pub name: &'a str,
+    /// `true` if the entry is a directory.
+    pub is_dir: bool,
+    /// Size of the file in bytes, or `0` for directories.
+    pub size: i32,
+}
+
+/// Iterator over the entries of a [`FileList`].
+pub struct FileEntries<'a> {
+    unpacker: Unpacker<'a>,
+}
+
+impl<'a> Iterator for FileEntries<'a> {
+    type Item = FileEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.unpacker.has_remaining() {
+            return None;
+        }
+        Some(FileEntry {
+            name: self.unpacker.unpack_str().ok()?,
+            is_dir: self.unpacker.unpack_bool().ok()?,
+            size: self.unpacker.unpack_i32().ok()?,
+        })
+    }
+}
+
+/// A chunk of a file, returned in response to [`Command::FileRead`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FileChunk<const N: usize = DEFAULT_BUFFER_LEN> {
+    /// Offset of the chunk within the file.
+    pub offset: u32,
+    /// Total size of the file, or a negative value if the file could not be
+    /// read.
+    pub size: i32,
+    /// The chunk itself.
+    pub data: ReplyData<N>,
+}
+
+/// Log samples of consecutive fields, sent after [`Command::LogStart`] as
+/// [`CommandReply::LogDataF32`] or [`CommandReply::LogDataF64`].
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LogSamples<const N: usize = DEFAULT_BUFFER_LEN> {
+    /// Index of the field the first sample belongs to.
+    pub first_field: u16,
+    is_f64: bool,
+    data: ReplyData<N>,
+}
+
+impl<const N: usize> LogSamples<N> {
+    /// Returns the number of samples.
+    pub fn len(&self) -> usize {
+        self.data.len() / self.sample_len()
+    }
+
+    /// Returns `true` if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the sample at the given position, i.e. of the field
+    /// `first_field + index`.
+    pub fn get(&self, index: usize) -> Option<f64> {
+        let len = self.sample_len();
+        let bytes = self.data.get(index * len..(index + 1) * len)?;
+        let mut unpacker = Unpacker::new(bytes);
+        if self.is_f64 {
+            unpacker.unpack_f64_auto().ok()
+        } else {
+            unpacker.unpack_f32_auto().ok().map(f64::from)
+        }
+    }
+
+    /// Returns an iterator over the samples.
+    pub fn iter(&self) -> LogSamplesIter<'_, N> {
+        LogSamplesIter {
+            samples: self,
+            index: 0,
+        }
+    }
+
+    fn sample_len(&self) -> usize {
+        if self.is_f64 { 8 } else { 4 }
+    }
+}
+
+/// Iterator over the samples of [`LogSamples`].
+pub struct LogSamplesIter<'a, const N: usize = DEFAULT_BUFFER_LEN> {
+    samples: &'a LogSamples<N>,
+    index: usize,
+}
+
+impl<const N: usize> Iterator for LogSamplesIter<'_, N> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.samples.get(self.index)?;
+        self.index += 1;
+        Some(sample)
+    }
 }
 
 /// Reply messages received from the VESC in response to commands.
 ///
 /// These represent the various types of responses that can be received from the
 /// controller after sending commands.
+#[expect(
+    clippy::large_enum_variant,
+    reason = "BMS readings are kept in fixed arrays, as there is no allocator to box them"
+)]
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub enum CommandReply {
+pub enum CommandReply<const N: usize = DEFAULT_BUFFER_LEN> {
+    /// Result of [`Command::EraseNewApp`], `true` if the flash area has been
+    /// erased successfully.
+    EraseNewApp(bool),
+
+    /// Result of [`Command::WriteNewAppData`]. Contains `true` if the chunk has
+    /// been written successfully, and the offset of the written chunk.
+    WriteNewAppData(bool, u32),
+
+    /// A sample in response to [`Command::SamplePrint`].
+    SamplePrint(MotorSample),
+
+    /// Rotor position in degrees. Sent unsolicited while the position display
+    /// mode is enabled.
+    RotorPosition(f32),
+
+    /// Values sampled by a firmware experiment. Sent unsolicited.
+    ExperimentSample(ExperimentSamples),
+
+    /// Result of [`Command::DetectMotorParam`].
+    DetectMotorParam(BldcDetection),
+
+    /// Result of [`Command::DetectMotorRL`].
+    DetectMotorRL(ResistanceInductance),
+
+    /// Result of [`Command::DetectMotorFluxLinkage`], the flux linkage in
+    /// webers.
+    DetectMotorFluxLinkage(f32),
+
+    /// Result of [`Command::DetectEncoder`].
+    DetectEncoder(EncoderDetection),
+
+    /// Result of [`Command::DetectHallFoc`].
+    DetectHallFoc(HallDetection),
+
+    /// Input decoded by the PPM app in response to [`Command::GetDecodedPpm`].
+    GetDecodedPpm(DecodedPpm),
+
+    /// Input decoded by the ADC app in response to [`Command::GetDecodedAdc`].
+    GetDecodedAdc(DecodedAdc),
+
+    /// Input decoded by the Nunchuk app in response to
+    /// [`Command::GetDecodedChuk`], ranging from `-1.0` to `1.0`.
+    GetDecodedChuk(f32),
+
+    /// Opaque data sent by a custom application running on the VESC, in
+    /// response to [`Command::CustomAppData`] or on its own.
+    CustomAppData(ReplyData<N>),
+
     /// Complete telemetry data in response to [`Command::GetValues`]. Contains
     /// all available sensor readings and status information.
     GetValues(Values),
 
+    /// Complete setup telemetry data in response to
+    /// [`Command::GetValuesSetup`].
+    GetValuesSetup(SetupValues),
+
     /// Selective telemetry data in response to [`Command::GetValuesSelective`].
     /// Contains only the data fields that were requested via the
     /// [`ValuesMask`]. Non-requested fields will have default values.
     GetValuesSelective(Values),
+
+    /// Selective setup telemetry data in response to
+    /// [`Command::GetValuesSetupSelective`]. Contains only the data fields that
+    /// were requested via the [`SetupValuesMask`]. Non-requested fields will
+    /// have default values.
+    GetValuesSetupSelective(SetupValues),
+
+    /// Result of [`Command::DetectMotorFluxLinkageOpenloop`].
+    DetectMotorFluxLinkageOpenloop(FluxLinkageDetection),
+
+    /// Result of [`Command::DetectApplyAllFoc`]. Positive values indicate
+    /// success, negative values indicate the step that failed.
+    DetectApplyAllFoc(i16),
+
+    /// IMU data in response to [`Command::GetImuData`]. Contains only the data
+    /// fields that were requested via the [`ImuMask`]. Non-requested fields
+    /// will have default values.
+    GetImuData(ImuData),
+
+    /// Result of [`Command::EraseBootloader`], `true` if the flash area has
+    /// been erased successfully.
+    EraseBootloader(bool),
+
+    /// Result of [`Command::EraseBootloaderAllCan`], `true` if the flash area
+    /// of the receiving controller has been erased successfully.
+    EraseBootloaderAllCan(bool),
+
+    /// Starts a new plot with the given axis names, dropping all graphs. Sent
+    /// unsolicited by the firmware or an application, e.g. a LispBM script.
+    PlotInit(PlotAxisNames<N>),
+
+    /// A point added to the active graph, as the x and y coordinates.
+    PlotData(f32, f32),
+
+    /// Adds a graph with the given name to the plot.
+    PlotAddGraph(ReplyData<N>),
+
+    /// Makes the graph with the given index the active one, i.e. the one
+    /// subsequent points are added to.
+    PlotSetGraph(u8),
+
+    /// State of the balance app in response to [`Command::GetDecodedBalance`].
+    GetDecodedBalance(DecodedBalance),
+
+    /// Result of [`Command::EraseNewAppAllCan`], `true` if the flash area of
+    /// the receiving controller has been erased successfully.
+    EraseNewAppAllCan(bool),
+
+    /// Result of [`Command::WriteNewAppDataAllCan`]. Contains `true` if the
+    /// chunk has been written successfully by the receiving controller, and
+    /// the offset of the written chunk.
+    WriteNewAppDataAllCan(bool, u32),
+
+    /// Result of [`Command::WriteNewAppDataLzo`]. Contains `true` if the chunk
+    /// has been decompressed and written successfully, and the offset of the
+    /// written chunk.
+    WriteNewAppDataLzo(bool, u32),
+
+    /// Result of [`Command::WriteNewAppDataAllCanLzo`]. Contains `true` if the
+    /// chunk has been decompressed and written successfully by the receiving
+    /// controller, and the offset of the written chunk.
+    WriteNewAppDataAllCanLzo(bool, u32),
+
+    /// A raw frame received on the CAN bus. Relayed unsolicited by
+    /// controllers in the communication bridge CAN mode.
+    CanFwdFrame(CanFrame),
+
+    /// Acknowledges [`Command::SetBatteryCut`].
+    SetBatteryCut,
+
+    /// Result of [`Command::SetBleName`], `true` if the BLE module has been
+    /// renamed successfully.
+    SetBleName(bool),
+
+    /// Result of [`Command::SetBlePin`], `true` if the PIN has been set
+    /// successfully.
+    SetBlePin(bool),
+
+    /// Acknowledges [`Command::SetCanMode`], if requested.
+    SetCanMode,
+
+    /// IMU calibration in response to [`Command::GetImuCalibration`].
+    GetImuCalibration(ImuCalibration),
+
+    /// A chunk of the custom config XML in response to
+    /// [`Command::GetCustomConfigXml`].
+    GetCustomConfigXml(ConfigXmlChunk<N>),
+
+    /// Serialized values of a custom config in response to
+    /// [`Command::GetCustomConfig`]. Contains the config index and the values.
+    GetCustomConfig(u8, ReplyData<N>),
+
+    /// Serialized default values of a custom config in response to
+    /// [`Command::GetCustomConfigDefault`]. Contains the config index and the
+    /// values.
+    GetCustomConfigDefault(u8, ReplyData<N>),
+
+    /// Acknowledges [`Command::SetCustomConfig`]. Only sent if the values have
+    /// been applied successfully.
+    SetCustomConfig,
+
+    /// Values of the BMS in response to [`Command::BmsGetValues`].
+    BmsGetValues(BmsValues),
+
+    /// Result of [`Command::EraseNewAppHw`], `true` if the flash area has been
+    /// erased successfully.
+    EraseNewAppHw(bool),
+
+    /// Result of [`Command::WriteNewAppDataHw`]. Contains `true` if the chunk
+    /// has been written successfully, and the offset of the written chunk.
+    WriteNewAppDataHw(bool, u32),
+
+    /// Result of [`Command::EraseBootloaderHw`], `true` if the flash area has
+    /// been erased successfully.
+    EraseBootloaderHw(bool),
+
+    /// Result of [`Command::EraseNewAppAllCanHw`], `true` if the flash area of
+    /// the receiving device has been erased successfully.
+    EraseNewAppAllCanHw(bool),
+
+    /// Result of [`Command::WriteNewAppDataAllCanHw`]. Contains `true` if the
+    /// chunk has been written successfully by the receiving device, and the
+    /// offset of the written chunk.
+    WriteNewAppDataAllCanHw(bool, u32),
+
+    /// Result of [`Command::EraseBootloaderAllCanHw`], `true` if the flash
+    /// area of the receiving device has been erased successfully.
+    EraseBootloaderAllCanHw(bool),
+
+    /// Statistics in response to [`Command::GetStats`]. Contains only the data
+    /// fields that were requested via the [`StatsMask`]. Non-requested fields
+    /// will have default values.
+    GetStats(StatsValues),
+
+    /// Acknowledges [`Command::ResetStats`], if requested.
+    ResetStats,
+
+    /// Part of the LispBM code in response to [`Command::LispReadCode`].
+    /// Contains the total length of the code, the offset of the part and the
+    /// part itself.
+    LispReadCode(u32, u32, ReplyData<N>),
+
+    /// Result of [`Command::LispWriteCode`]. Contains `true` if the data has
+    /// been written successfully, and the offset of the written data.
+    LispWriteCode(bool, u32),
+
+    /// Result of [`Command::LispEraseCode`], `true` if the code area has been
+    /// erased successfully.
+    LispEraseCode(bool),
+
+    /// Result of [`Command::LispSetRunning`], `true` on success.
+    LispSetRunning(bool),
+
+    /// LispBM runtime statistics in response to [`Command::LispGetStats`].
+    LispGetStats(LispStats<N>),
+
+    /// Text printed by the LispBM script or the REPL. Sent unsolicited.
+    LispPrint(ReplyData<N>),
+
+    /// A page of directory entries in response to [`Command::FileList`].
+    FileList(FileList<N>),
+
+    /// A chunk of a file in response to [`Command::FileRead`].
+    FileRead(FileChunk<N>),
+
+    /// Result of [`Command::FileWrite`]. Contains `true` if the chunk has been
+    /// written successfully, and the offset of the written chunk.
+    FileWrite(bool, u32),
+
+    /// Result of [`Command::FileMkdir`], `true` if the directory has been
+    /// created successfully.
+    FileMkdir(bool),
+
+    /// Result of [`Command::FileRemove`], `true` if the entry has been
+    /// removed successfully.
+    FileRemove(bool),
+
+    /// Single precision log samples. Sent unsolicited after
+    /// [`Command::LogStart`].
+    LogDataF32(LogSamples<N>),
+
+    /// GNSS data in response to [`Command::GetGnss`]. Contains only the data
+    /// fields that were requested via the [`GnssMask`]. Non-requested fields
+    /// will have default values.
+    GetGnss(GnssData),
+
+    /// Double precision log samples. Sent unsolicited after
+    /// [`Command::LogStart`].
+    LogDataF64(LogSamples<N>),
+
+    /// Result of [`Command::CanUpdateBaudAll`], `true` if the baud rate is
+    /// supported and has been applied.
+    CanUpdateBaudAll(bool),
 }
 
-impl CommandReply {
+impl<const N: usize> CommandReply<N> {
     fn unpack_from(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
         Ok(match unpacker.unpack_u8()?.try_into()? {
+            CommandId::EraseNewApp => Self::EraseNewApp(unpacker.unpack_bool()?),
+            CommandId::WriteNewAppData => {
+                Self::WriteNewAppData(unpacker.unpack_bool()?, unpacker.unpack_u32()?)
+            }
+            CommandId::SamplePrint => Self::SamplePrint(MotorSample {
+                current1: unpacker.unpack_f32_auto()?,
+                current2: unpacker.unpack_f32_auto()?,
+                voltage1: unpacker.unpack_f32_auto()?,
+                voltage2: unpacker.unpack_f32_auto()?,
+                voltage3: unpacker.unpack_f32_auto()?,
+                voltage_zero: unpacker.unpack_f32_auto()?,
+                current_total: unpacker.unpack_f32_auto()?,
+                switching_frequency: unpacker.unpack_f32_auto()?,
+                status: unpacker.unpack_u8()?,
+                phase: unpacker.unpack_u8()?,
+            }),
+            CommandId::RotorPosition => Self::RotorPosition(unpacker.unpack_f32(100000.0)?),
+            CommandId::ExperimentSample => Self::unpack_experiment_sample(unpacker)?,
+            CommandId::DetectMotorParam => Self::unpack_detect_motor_param(unpacker)?,
+            CommandId::DetectMotorRL => Self::unpack_detect_motor_r_l(unpacker)?,
+            CommandId::DetectMotorFluxLinkage => {
+                Self::DetectMotorFluxLinkage(unpacker.unpack_f32(10000000.0)?)
+            }
+            CommandId::DetectEncoder => Self::unpack_detect_encoder(unpacker)?,
+            CommandId::DetectHallFoc => Self::unpack_detect_hall_foc(unpacker)?,
+            CommandId::GetDecodedPpm => Self::GetDecodedPpm(DecodedPpm {
+                value: unpacker.unpack_f32(1000000.0)?,
+                pulse_len: unpacker.unpack_f32(1000000.0)?,
+            }),
+            CommandId::GetDecodedAdc => Self::GetDecodedAdc(DecodedAdc {
+                value: unpacker.unpack_f32(1000000.0)?,
+                voltage: unpacker.unpack_f32(1000000.0)?,
+                value2: unpacker.unpack_f32(1000000.0)?,
+                voltage2: unpacker.unpack_f32(1000000.0)?,
+            }),
+            CommandId::GetDecodedChuk => Self::GetDecodedChuk(unpacker.unpack_f32(1000000.0)?),
+            CommandId::CustomAppData => {
+                Self::CustomAppData(ReplyData::new(unpacker.unpack_remaining())?)
+            }
             CommandId::GetValues => Self::unpack_get_values(unpacker)?,
+            CommandId::GetValuesSetup => {
+                Self::GetValuesSetup(Self::unpack_setup_values(unpacker, SetupValuesMask::all())?)
+            }
             CommandId::GetValuesSelective => Self::unpack_get_values_selective(unpacker)?,
+            CommandId::GetValuesSetupSelective => {
+                let mask = SetupValuesMask::from_bits_retain(unpacker.unpack_u32()?);
+                Self::GetValuesSetupSelective(Self::unpack_setup_values(unpacker, mask)?)
+            }
+            CommandId::DetectMotorFluxLinkageOpenloop => {
+                Self::unpack_detect_motor_flux_linkage_openloop(unpacker)?
+            }
+            CommandId::DetectApplyAllFoc => Self::DetectApplyAllFoc(unpacker.unpack_i16()?),
+            CommandId::EraseNewAppAllCan => Self::EraseNewAppAllCan(unpacker.unpack_bool()?),
+            CommandId::WriteNewAppDataAllCan => {
+                Self::WriteNewAppDataAllCan(unpacker.unpack_bool()?, unpacker.unpack_u32()?)
+            }
+            CommandId::GetImuData => Self::unpack_get_imu_data(unpacker)?,
+            CommandId::EraseBootloader => Self::EraseBootloader(unpacker.unpack_bool()?),
+            CommandId::EraseBootloaderAllCan => {
+                Self::EraseBootloaderAllCan(unpacker.unpack_bool()?)
+            }
+            CommandId::PlotInit => Self::unpack_plot_init(unpacker)?,
+            CommandId::PlotData => {
+                Self::PlotData(unpacker.unpack_f32_auto()?, unpacker.unpack_f32_auto()?)
+            }
+            CommandId::PlotAddGraph => {
+                Self::PlotAddGraph(ReplyData::new(unpacker.unpack_str()?.as_bytes())?)
+            }
+            CommandId::PlotSetGraph => Self::PlotSetGraph(unpacker.unpack_u8()?),
+            CommandId::GetDecodedBalance => Self::unpack_get_decoded_balance(unpacker)?,
+            CommandId::WriteNewAppDataLzo => {
+                Self::WriteNewAppDataLzo(unpacker.unpack_bool()?, unpacker.unpack_u32()?)
+            }
+            CommandId::WriteNewAppDataAllCanLzo => {
+                Self::WriteNewAppDataAllCanLzo(unpacker.unpack_bool()?, unpacker.unpack_u32()?)
+            }
+            CommandId::CanFwdFrame => Self::unpack_can_fwd_frame(unpacker)?,
+            CommandId::SetBatteryCut => Self::SetBatteryCut,
+            CommandId::SetBleName => Self::SetBleName(unpacker.unpack_bool()?),
+            CommandId::SetBlePin => Self::SetBlePin(unpacker.unpack_bool()?),
+            CommandId::SetCanMode => Self::SetCanMode,
+            CommandId::GetImuCalibration => Self::unpack_get_imu_calibration(unpacker)?,
+            CommandId::GetCustomConfigXml => Self::GetCustomConfigXml(ConfigXmlChunk {
+                index: unpacker.unpack_u8()?,
+                total_len: unpacker.unpack_u32()?,
+                offset: unpacker.unpack_u32()?,
+                data: ReplyData::new(unpacker.unpack_remaining())?,
+            }),
+            CommandId::GetCustomConfig => Self::GetCustomConfig(
+                unpacker.unpack_u8()?,
+                ReplyData::new(unpacker.unpack_remaining())?,
+            ),
+            CommandId::GetCustomConfigDefault => Self::GetCustomConfigDefault(
+                unpacker.unpack_u8()?,
+                ReplyData::new(unpacker.unpack_remaining())?,
+            ),
+            CommandId::SetCustomConfig => Self::SetCustomConfig,
+            CommandId::BmsGetValues => Self::unpack_bms_get_values(unpacker)?,
+            CommandId::EraseNewAppHw => Self::EraseNewAppHw(unpacker.unpack_bool()?),
+            CommandId::WriteNewAppDataHw => {
+                Self::WriteNewAppDataHw(unpacker.unpack_bool()?, unpacker.unpack_u32()?)
+            }
+            CommandId::EraseBootloaderHw => Self::EraseBootloaderHw(unpacker.unpack_bool()?),
+            CommandId::EraseNewAppAllCanHw => Self::EraseNewAppAllCanHw(unpacker.unpack_bool()?),
+            CommandId::WriteNewAppDataAllCanHw => {
+                Self::WriteNewAppDataAllCanHw(unpacker.unpack_bool()?, unpacker.unpack_u32()?)
+            }
+            CommandId::EraseBootloaderAllCanHw => {
+                Self::EraseBootloaderAllCanHw(unpacker.unpack_bool()?)
+            }
+            CommandId::GetStats => Self::unpack_get_stats(unpacker)?,
+            CommandId::ResetStats => Self::ResetStats,
+            CommandId::LispReadCode => Self::LispReadCode(
+                unpacker.unpack_u32()?,
+                unpacker.unpack_u32()?,
+                ReplyData::new(unpacker.unpack_remaining())?,
+            ),
+            CommandId::LispWriteCode => {
+                Self::LispWriteCode(unpacker.unpack_bool()?, unpacker.unpack_u32()?)
+            }
+            CommandId::LispEraseCode => Self::LispEraseCode(unpacker.unpack_bool()?),
+            CommandId::LispSetRunning => Self::LispSetRunning(unpacker.unpack_bool()?),
+            CommandId::LispGetStats => Self::unpack_lisp_get_stats(unpacker)?,
+            CommandId::LispPrint => {
+                let text = unpacker.unpack_remaining();
+                let len = text
+                    .iter()
+                    .rposition(|byte| *byte != 0)
+                    .map_or(0, |pos| pos + 1);
+                Self::LispPrint(ReplyData::new(&text[..len])?)
+            }
+            CommandId::FileList => Self::unpack_file_list(unpacker)?,
+            CommandId::FileRead => Self::FileRead(FileChunk {
+                offset: unpacker.unpack_u32()?,
+                size: unpacker.unpack_i32()?,
+                data: ReplyData::new(unpacker.unpack_remaining())?,
+            }),
+            CommandId::FileWrite => {
+                let offset = unpacker.unpack_u32()?;
+                Self::FileWrite(unpacker.unpack_bool()?, offset)
+            }
+            CommandId::FileMkdir => Self::FileMkdir(unpacker.unpack_bool()?),
+            CommandId::FileRemove => Self::FileRemove(unpacker.unpack_bool()?),
+            CommandId::LogDataF32 => Self::LogDataF32(Self::unpack_log_samples(unpacker, false)?),
+            CommandId::GetGnss => Self::unpack_get_gnss(unpacker)?,
+            CommandId::LogDataF64 => Self::LogDataF64(Self::unpack_log_samples(unpacker, true)?),
+            CommandId::CanUpdateBaudAll => Self::CanUpdateBaudAll(unpacker.unpack_bool()?),
             id => return Err(DecodeError::UnknownPacket { id: id as u8 }),
         })
     }
 
+    fn unpack_can_fwd_frame(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut frame = CanFrame {
+            id: unpacker.unpack_u32()?,
+            is_extended: unpacker.unpack_bool()?,
+            ..Default::default()
+        };
+        let data = unpacker.unpack_remaining();
+        frame.len = data.len();
+        frame
+            .data
+            .get_mut(..frame.len)
+            .ok_or(DecodeError::CapacityExceeded)?
+            .copy_from_slice(data);
+        Ok(CommandReply::CanFwdFrame(frame))
+    }
+
+    fn unpack_experiment_sample(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut samples = ExperimentSamples::default();
+        while unpacker.has_remaining() {
+            let sample = samples
+                .samples
+                .get_mut(samples.len)
+                .ok_or(DecodeError::CapacityExceeded)?;
+            *sample = unpacker.unpack_f32(10000.0)?;
+            samples.len += 1;
+        }
+        Ok(CommandReply::ExperimentSample(samples))
+    }
+
+    fn unpack_detect_motor_param(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let detection = BldcDetection {
+            cycle_int_limit: unpacker.unpack_f32(1000.0)?,
+            coupling_k: unpacker.unpack_f32(1000.0)?,
+            hall_table: unpacker.unpack_bytes(8)?.try_into().unwrap(),
+            hall_res: unpacker.unpack_i8()?,
+        };
+        Ok(CommandReply::DetectMotorParam(detection))
+    }
+
+    fn unpack_detect_motor_r_l(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut detection = ResistanceInductance {
+            resistance: unpacker.unpack_f32(1000000.0)?,
+            inductance: unpacker.unpack_f32(1000.0)?,
+            ..Default::default()
+        };
+        if unpacker.has_remaining() {
+            detection.ld_lq_diff = unpacker.unpack_f32(1000.0)?;
+        }
+        Ok(CommandReply::DetectMotorRL(detection))
+    }
+
+    fn unpack_detect_motor_flux_linkage_openloop(
+        unpacker: &mut Unpacker,
+    ) -> Result<Self, DecodeError> {
+        let mut detection = FluxLinkageDetection {
+            flux_linkage: unpacker.unpack_f32(10000000.0)?,
+            ..Default::default()
+        };
+        if unpacker.has_remaining() {
+            detection.encoder_offset = unpacker.unpack_f32(1000000.0)?;
+            detection.encoder_ratio = unpacker.unpack_f32(1000000.0)?;
+            detection.encoder_inverted = unpacker.unpack_bool()?;
+        }
+        Ok(CommandReply::DetectMotorFluxLinkageOpenloop(detection))
+    }
+
+    fn unpack_detect_encoder(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let detection = EncoderDetection {
+            offset: unpacker.unpack_f32(1000000.0)?,
+            ratio: unpacker.unpack_f32(1000000.0)?,
+            inverted: unpacker.unpack_bool()?,
+        };
+        Ok(CommandReply::DetectEncoder(detection))
+    }
+
+    fn unpack_detect_hall_foc(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let detection = HallDetection {
+            hall_table: unpacker.unpack_bytes(8)?.try_into().unwrap(),
+            // The firmware reports `0` on success.
+            success: !unpacker.unpack_bool()?,
+        };
+        Ok(CommandReply::DetectHallFoc(detection))
+    }
+
     fn unpack_get_values(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
         let values = Values {
             temp_mosfet: unpacker.unpack_f16(10.0)?,
@@ -478,12 +3043,333 @@ impl CommandReply {
         }
         Ok(CommandReply::GetValuesSelective(values))
     }
+
+    fn unpack_get_imu_data(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut data = ImuData::default();
+        let mask = ImuMask::from_bits_retain(unpacker.unpack_u16()?);
+
+        for (flag, field) in [
+            (ImuMask::ROLL, &mut data.roll),
+            (ImuMask::PITCH, &mut data.pitch),
+            (ImuMask::YAW, &mut data.yaw),
+            (ImuMask::ACC_X, &mut data.acc_x),
+            (ImuMask::ACC_Y, &mut data.acc_y),
+            (ImuMask::ACC_Z, &mut data.acc_z),
+            (ImuMask::GYRO_X, &mut data.gyro_x),
+            (ImuMask::GYRO_Y, &mut data.gyro_y),
+            (ImuMask::GYRO_Z, &mut data.gyro_z),
+            (ImuMask::MAG_X, &mut data.mag_x),
+            (ImuMask::MAG_Y, &mut data.mag_y),
+            (ImuMask::MAG_Z, &mut data.mag_z),
+            (ImuMask::Q0, &mut data.q0),
+            (ImuMask::Q1, &mut data.q1),
+            (ImuMask::Q2, &mut data.q2),
+            (ImuMask::Q3, &mut data.q3),
+        ] {
+            if mask.contains(flag) {
+                *field = unpacker.unpack_f32_auto()?;
+            }
+        }
+        Ok(CommandReply::GetImuData(data))
+    }
+
+    fn unpack_get_decoded_balance(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut balance = DecodedBalance {
+            pid_output: unpacker.unpack_f32(1000000.0)?,
+            pitch: unpacker.unpack_f32(1000000.0)?,
+            roll: unpacker.unpack_f32(1000000.0)?,
+            loop_time_us: unpacker.unpack_u32()?,
+            motor_current: unpacker.unpack_f32(1000000.0)?,
+            state: unpacker.unpack_u16()?,
+            switch_state: unpacker.unpack_u16()?,
+            adc1: unpacker.unpack_f32(1000000.0)?,
+            adc2: unpacker.unpack_f32(1000000.0)?,
+            ..Default::default()
+        };
+        if unpacker.has_remaining() {
+            balance.debug1 = unpacker.unpack_f32(1000000.0)?;
+            balance.debug2 = unpacker.unpack_f32(1000000.0)?;
+        }
+        Ok(CommandReply::GetDecodedBalance(balance))
+    }
+
+    fn unpack_get_imu_calibration(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let calibration = ImuCalibration {
+            roll: unpacker.unpack_f32(1000000.0)?,
+            pitch: unpacker.unpack_f32(1000000.0)?,
+            yaw: unpacker.unpack_f32(1000000.0)?,
+            acc_offset_x: unpacker.unpack_f32(1000000.0)?,
+            acc_offset_y: unpacker.unpack_f32(1000000.0)?,
+            acc_offset_z: unpacker.unpack_f32(1000000.0)?,
+            gyro_offset_x: unpacker.unpack_f32(1000000.0)?,
+            gyro_offset_y: unpacker.unpack_f32(1000000.0)?,
+            gyro_offset_z: unpacker.unpack_f32(1000000.0)?,
+        };
+        Ok(CommandReply::GetImuCalibration(calibration))
+    }
+
+    fn unpack_bms_get_values(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut values = BmsValues {
+            voltage: unpacker.unpack_f32(1000000.0)?,
+            charge_voltage: unpacker.unpack_f32(1000000.0)?,
+            current: unpacker.unpack_f32(1000000.0)?,
+            current_ic: unpacker.unpack_f32(1000000.0)?,
+            amp_hours: unpacker.unpack_f32(1000.0)?,
+            watt_hours: unpacker.unpack_f32(1000.0)?,
+            ..Default::default()
+        };
+
+        values.cell_count = unpacker.unpack_u8()? as usize;
+        if values.cell_count > BMS_MAX_CELLS {
+            return Err(DecodeError::CapacityExceeded);
+        }
+        for voltage in values.cell_voltage[..values.cell_count].iter_mut() {
+            *voltage = unpacker.unpack_f16(1000.0)?;
+        }
+        for balancing in values.cell_balancing_state[..values.cell_count].iter_mut() {
+            *balancing = unpacker.unpack_bool()?;
+        }
+
+        values.temp_count = unpacker.unpack_u8()? as usize;
+        if values.temp_count > BMS_MAX_TEMPS {
+            return Err(DecodeError::CapacityExceeded);
+        }
+        for temp in values.temp[..values.temp_count].iter_mut() {
+            *temp = unpacker.unpack_f16(100.0)?;
+        }
+
+        values.temp_ic = unpacker.unpack_f16(100.0)?;
+        values.temp_humidity_sensor = unpacker.unpack_f16(100.0)?;
+        values.humidity = unpacker.unpack_f16(100.0)?;
+        values.temp_cell_max = unpacker.unpack_f16(100.0)?;
+        values.soc = unpacker.unpack_f16(1000.0)?;
+        values.soh = unpacker.unpack_f16(1000.0)?;
+        values.can_id = unpacker.unpack_u8()?;
+
+        if unpacker.has_remaining() {
+            values.amp_hours_charged_total = unpacker.unpack_f32_auto()?;
+            values.watt_hours_charged_total = unpacker.unpack_f32_auto()?;
+            values.amp_hours_discharged_total = unpacker.unpack_f32_auto()?;
+            values.watt_hours_discharged_total = unpacker.unpack_f32_auto()?;
+        }
+        if unpacker.has_remaining() {
+            values.pressure = unpacker.unpack_f16(0.1)?;
+        }
+        Ok(CommandReply::BmsGetValues(values))
+    }
+
+    fn unpack_get_stats(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut values = StatsValues::default();
+        // The request mask is 16 bits wide, the echoed one 32 bits.
+        let mask = StatsMask::from_bits_retain(unpacker.unpack_u32()? as u16);
+
+        for (flag, field) in [
+            (StatsMask::SPEED_AVG, &mut values.speed_avg),
+            (StatsMask::SPEED_MAX, &mut values.speed_max),
+            (StatsMask::POWER_AVG, &mut values.power_avg),
+            (StatsMask::POWER_MAX, &mut values.power_max),
+            (StatsMask::CURRENT_AVG, &mut values.current_avg),
+            (StatsMask::CURRENT_MAX, &mut values.current_max),
+            (StatsMask::TEMP_MOSFET_AVG, &mut values.temp_mosfet_avg),
+            (StatsMask::TEMP_MOSFET_MAX, &mut values.temp_mosfet_max),
+            (StatsMask::TEMP_MOTOR_AVG, &mut values.temp_motor_avg),
+            (StatsMask::TEMP_MOTOR_MAX, &mut values.temp_motor_max),
+            (StatsMask::COUNT_TIME, &mut values.count_time),
+        ] {
+            if mask.contains(flag) {
+                *field = unpacker.unpack_f32_auto()?;
+            }
+        }
+        Ok(CommandReply::GetStats(values))
+    }
+
+    fn unpack_get_gnss(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut data = GnssData::default();
+        let mask = GnssMask::from_bits_retain(unpacker.unpack_u16()?);
+
+        if mask.contains(GnssMask::LATITUDE) {
+            data.latitude = unpacker.unpack_f64(1e16)?;
+        }
+        if mask.contains(GnssMask::LONGITUDE) {
+            data.longitude = unpacker.unpack_f64(1e16)?;
+        }
+        if mask.contains(GnssMask::HEIGHT) {
+            data.height = unpacker.unpack_f32_auto()?;
+        }
+        if mask.contains(GnssMask::SPEED) {
+            data.speed = unpacker.unpack_f32_auto()?;
+        }
+        if mask.contains(GnssMask::HDOP) {
+            data.hdop = unpacker.unpack_f32_auto()?;
+        }
+        if mask.contains(GnssMask::MS_TODAY) {
+            data.ms_today = unpacker.unpack_i32()?;
+        }
+        if mask.contains(GnssMask::YEAR) {
+            data.year = unpacker.unpack_i16()?;
+        }
+        if mask.contains(GnssMask::MONTH) {
+            data.month = unpacker.unpack_i8()?;
+        }
+        if mask.contains(GnssMask::DAY) {
+            data.day = unpacker.unpack_i8()?;
+        }
+        if mask.contains(GnssMask::AGE) {
+            data.age = unpacker.unpack_f32_auto()?;
+        }
+        Ok(CommandReply::GetGnss(data))
+    }
+
+    fn unpack_lisp_get_stats(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut stats = LispStats {
+            cpu_use: unpacker.unpack_f16(100.0)?,
+            heap_use: unpacker.unpack_f16(100.0)?,
+            mem_use: unpacker.unpack_f16(100.0)?,
+            stack_use: unpacker.unpack_f16(100.0)?,
+            data: ReplyData::new(unpacker.unpack_remaining())?,
+            bindings_pos: 0,
+        };
+
+        // Validate the strings upfront, so that the accessors can't fail.
+        let mut data = Unpacker::new(stats.data.as_slice());
+        data.unpack_str()?;
+        stats.bindings_pos = data.pos;
+        while data.has_remaining() {
+            data.unpack_str()?;
+            data.unpack_f32_auto()?;
+        }
+        Ok(CommandReply::LispGetStats(stats))
+    }
+
+    fn unpack_file_list(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let list = FileList {
+            has_more: unpacker.unpack_bool()?,
+            data: ReplyData::new(unpacker.unpack_remaining())?,
+        };
+
+        // Validate the entries upfront, so that the iterator can't fail.
+        let mut data = Unpacker::new(list.data.as_slice());
+        while data.has_remaining() {
+            data.unpack_str()?;
+            data.unpack_bool()?;
+            data.unpack_i32()?;
+        }
+        Ok(CommandReply::FileList(list))
+    }
+
+    fn unpack_plot_init(unpacker: &mut Unpacker) -> Result<Self, DecodeError> {
+        let mut names = PlotAxisNames {
+            data: ReplyData::new(unpacker.unpack_remaining())?,
+            x_len: 0,
+            y_len: 0,
+        };
+
+        // Validate the strings upfront, so that the accessors can't fail.
+        let mut data = Unpacker::new(names.data.as_slice());
+        names.x_len = data.unpack_str()?.len();
+        names.y_len = data.unpack_str()?.len();
+        if data.has_remaining() {
+            return Err(DecodeError::InvalidFrame);
+        }
+        Ok(CommandReply::PlotInit(names))
+    }
+
+    fn unpack_log_samples(
+        unpacker: &mut Unpacker,
+        is_f64: bool,
+    ) -> Result<LogSamples<N>, DecodeError> {
+        let samples = LogSamples {
+            first_field: unpacker.unpack_u16()?,
+            is_f64,
+            data: ReplyData::new(unpacker.unpack_remaining())?,
+        };
+        if !samples.data.len().is_multiple_of(samples.sample_len()) {
+            return Err(DecodeError::InvalidFrame);
+        }
+        Ok(samples)
+    }
+
+    fn unpack_setup_values(
+        unpacker: &mut Unpacker,
+        mask: SetupValuesMask,
+    ) -> Result<SetupValues, DecodeError> {
+        let mut values = SetupValues::default();
+
+        if mask.contains(SetupValuesMask::TEMP_MOSFET) {
+            values.temp_mosfet = unpacker.unpack_f16(10.0)?;
+        }
+        if mask.contains(SetupValuesMask::TEMP_MOTOR) {
+            values.temp_motor = unpacker.unpack_f16(10.0)?;
+        }
+        if mask.contains(SetupValuesMask::CURRENT_MOTOR) {
+            values.current_motor = unpacker.unpack_f32(100.0)?;
+        }
+        if mask.contains(SetupValuesMask::CURRENT_INPUT) {
+            values.current_input = unpacker.unpack_f32(100.0)?;
+        }
+        if mask.contains(SetupValuesMask::DUTY_CYCLE) {
+            values.duty_cycle = unpacker.unpack_f16(1000.0)?;
+        }
+        if mask.contains(SetupValuesMask::RPM) {
+            values.rpm = unpacker.unpack_f32(1.0)?;
+        }
+        if mask.contains(SetupValuesMask::SPEED) {
+            values.speed = unpacker.unpack_f32(1000.0)?;
+        }
+        if mask.contains(SetupValuesMask::VOLTAGE_IN) {
+            values.voltage_in = unpacker.unpack_f16(10.0)?;
+        }
+        if mask.contains(SetupValuesMask::BATTERY_LEVEL) {
+            values.battery_level = unpacker.unpack_f16(1000.0)?;
+        }
+        if mask.contains(SetupValuesMask::AMP_HOURS) {
+            values.amp_hours = unpacker.unpack_f32(10000.0)?;
+        }
+        if mask.contains(SetupValuesMask::AMP_HOURS_CHARGED) {
+            values.amp_hours_charged = unpacker.unpack_f32(10000.0)?;
+        }
+        if mask.contains(SetupValuesMask::WATT_HOURS) {
+            values.watt_hours = unpacker.unpack_f32(10000.0)?;
+        }
+        if mask.contains(SetupValuesMask::WATT_HOURS_CHARGED) {
+            values.watt_hours_charged = unpacker.unpack_f32(10000.0)?;
+        }
+        if mask.contains(SetupValuesMask::DISTANCE) {
+            values.distance = unpacker.unpack_f32(1000.0)?;
+        }
+        if mask.contains(SetupValuesMask::DISTANCE_ABS) {
+            values.distance_abs = unpacker.unpack_f32(1000.0)?;
+        }
+        if mask.contains(SetupValuesMask::PID_POS) {
+            values.pid_pos = unpacker.unpack_f32(1000000.0)?;
+        }
+        if mask.contains(SetupValuesMask::FAULT_CODE) {
+            values.fault_code = unpacker.unpack_u8()?.into();
+        }
+        if mask.contains(SetupValuesMask::CONTROLLER_ID) {
+            values.controller_id = unpacker.unpack_u8()?;
+        }
+        if mask.contains(SetupValuesMask::NUM_VESCS) {
+            values.num_vescs = unpacker.unpack_u8()?;
+        }
+        if mask.contains(SetupValuesMask::BATTERY_WATT_HOURS) {
+            values.battery_watt_hours = unpacker.unpack_f32(1000.0)?;
+        }
+        if mask.contains(SetupValuesMask::ODOMETER) {
+            values.odometer = unpacker.unpack_u32()?;
+        }
+        if mask.contains(SetupValuesMask::UPTIME) {
+            values.uptime_ms = unpacker.unpack_u32()?;
+        }
+        Ok(values)
+    }
 }
 
 /// Encodes a [`Command`] into a byte buffer.
 ///
 /// Writes the encoded frame to `buf`. Returns the number of bytes written on
-/// success, or an error if encoding fails.
+/// success, or an error if encoding fails. Commands with a payload longer than
+/// 255 bytes are encoded as long frames, i.e. with a 16-bit payload length.
 ///
 /// # Example
 ///
@@ -501,13 +3387,37 @@ pub fn encode(command: Command, buf: &mut [u8]) -> Result<usize, EncodeError> {
     packer.pack_u8(FRAME_START_SHORT)?;
     packer.pack_u8(0)?;
     command.pack_into(&mut packer)?;
-    let payload_len = packer.pos - 2;
-    *packer.buf.get_mut(1).ok_or(EncodeError::BufferTooSmall)? = payload_len as u8;
+    let mut payload_start = 2;
+    let payload_len = packer.pos - payload_start;
+
+    if let Ok(payload_len) = u8::try_from(payload_len) {
+        *packer.buf.get_mut(1).ok_or(EncodeError::BufferTooSmall)? = payload_len;
+    } else {
+        let payload_len = u16::try_from(payload_len).map_err(|_| EncodeError::PayloadTooLarge)?;
+
+        // The long frame header is one byte longer than the short one, so the
+        // already packed payload has to be shifted to make room for it.
+        packer.pack_u8(0)?;
+        packer
+            .buf
+            .copy_within(payload_start..payload_start + payload_len as usize, 3);
+        packer
+            .buf
+            .get_mut(..3)
+            .ok_or(EncodeError::BufferTooSmall)?
+            .copy_from_slice(&[
+                FRAME_START_LONG,
+                (payload_len >> 8) as u8,
+                payload_len as u8,
+            ]);
+        payload_start = 3;
+    }
+
     packer.pack_u16(
         CRC16.checksum(
             packer
                 .buf
-                .get(2..2 + payload_len)
+                .get(payload_start..payload_start + payload_len)
                 .ok_or(EncodeError::BufferTooSmall)?,
         ),
     )?;
@@ -518,7 +3428,7 @@ pub fn encode(command: Command, buf: &mut [u8]) -> Result<usize, EncodeError> {
 /// Decodes a [`CommandReply`] from a byte buffer.
 ///
 /// Returns the consumed number of bytes and decoded reply on success, or an
-/// error if the frame is invalid.
+/// error if the frame is invalid. Both short and long frames are supported.
 ///
 /// # Example
 ///
@@ -534,31 +3444,55 @@ pub fn encode(command: Command, buf: &mut [u8]) -> Result<usize, EncodeError> {
 /// }
 /// ```
 pub fn decode(buf: &[u8]) -> Result<(usize, CommandReply), DecodeError> {
+    decode_frame(buf)
+}
+
+/// Decodes a [`CommandReply`] carrying data of up to `N` bytes from a byte
+/// buffer.
+pub(crate) fn decode_frame<const N: usize>(
+    buf: &[u8],
+) -> Result<(usize, CommandReply<N>), DecodeError> {
     let mut unpacker = Unpacker::new(buf);
 
-    let frame_start = unpacker.unpack_u8()?;
-    if frame_start != FRAME_START_SHORT {
-        return Err(DecodeError::InvalidFrame);
-    }
-    let payload_len = unpacker.unpack_u8()? as usize;
-    let reply = CommandReply::unpack_from(&mut unpacker)?;
+    let payload_len = match unpacker.unpack_u8()? {
+        FRAME_START_SHORT => unpacker.unpack_u8()? as usize,
+        FRAME_START_LONG => unpacker.unpack_u16()? as usize,
+        _ => return Err(DecodeError::InvalidFrame),
+    };
+    let payload_start = unpacker.pos;
+    let payload_end = payload_start + payload_len;
+    let is_complete = payload_end <= buf.len();
+
+    // The reply is unpacked from whatever part of the payload is available so
+    // far. This way a frame with a bogus payload length, e.g. one that starts
+    // at a random byte of a corrupted stream, is rejected as soon as possible
+    // instead of waiting for the declared number of bytes to arrive.
+    let mut payload = Unpacker::new(
+        buf.get(payload_start..payload_end.min(buf.len()))
+            .ok_or(DecodeError::Internal)?,
+    );
+    let reply = match CommandReply::unpack_from(&mut payload) {
+        Err(DecodeError::IncompleteData) if is_complete => return Err(DecodeError::InvalidFrame),
+        result => result?,
+    };
 
-    // Knowing the payload length upfront isn't strictly necessary here, but it
-    // provides an extra validation step: we can confirm that the frame is
-    // well-formed and report an error if the declared length doesn't match the
-    // actual payload length.
-    if payload_len != unpacker.pos - (frame_start as usize) {
+    // Knowing the payload length upfront isn't strictly necessary for most
+    // replies, but it provides an extra validation step: we can confirm that
+    // the frame is well-formed and report an error if the declared length
+    // doesn't match the actual payload length.
+    if payload.pos != payload_len {
+        if !is_complete && payload.pos == payload.buf.len() {
+            return Err(DecodeError::IncompleteData);
+        }
         return Err(DecodeError::InvalidFrame);
     }
-    let payload = &unpacker
-        .buf
-        .get((frame_start as usize)..unpacker.pos)
-        .ok_or(DecodeError::IncompleteData)?;
+    unpacker.pos = payload_end;
+
     let checksum_expected = unpacker.unpack_u16()?;
     if unpacker.unpack_u8()? != FRAME_END {
         return Err(DecodeError::InvalidFrame);
     }
-    let checksum_actual = CRC16.checksum(payload);
+    let checksum_actual = CRC16.checksum(payload.buf);
     if checksum_actual != checksum_expected {
         return Err(DecodeError::ChecksumMismatch {
             expected: checksum_expected,
@@ -0,0 +1,160 @@
+use crate::{Command, CommandReply, DEFAULT_CHUNK_SIZE};
+
+/// Errors that can occur during custom config XML download.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum DownloadError {
+    #[error("the XML of {required} bytes does not fit into the buffer")]
+    BufferTooSmall { required: u32 },
+
+    #[error("offset mismatch: expected {expected}, but found {actual}")]
+    OffsetMismatch { expected: u32, actual: u32 },
+
+    #[error("the reply does not belong to the pending download step")]
+    UnexpectedReply,
+}
+
+/// A state machine for downloading the XML describing a custom config, e.g. of
+/// a package, into a caller-supplied buffer.
+///
+/// The XML is requested in chunks. Each step yields a [`Command`] via
+/// [`next_command`] that must be sent to the controller, and the received
+/// reply must be passed to [`acknowledge`] before the next step is yielded.
+/// The first step requests an empty chunk to learn the total length of the
+/// XML, as the controller does not reply to requests beyond its end.
+///
+/// The firmware stores the XML compressed, hence the downloaded data must be
+/// decompressed before it can be parsed.
+///
+/// # Example
+///
+/// ```no_run
+/// use vesc::ConfigXmlDownload;
+///
+/// # fn send(_: &[u8]) {}
+/// # fn receive() -> vesc::CommandReply { unimplemented!() }
+/// let mut xml = [0u8; 8192];
+/// let mut download = ConfigXmlDownload::new(0, &mut xml);
+/// let mut buf = [0u8; 32];
+///
+/// while let Some(command) = download.next_command() {
+///     let len = vesc::encode(command, &mut buf).unwrap();
+///     send(&buf[..len]);
+///     download.acknowledge(&receive()).unwrap();
+/// }
+///
+/// let xml = download.xml();
+/// ```
+///
+/// [`next_command`]: Self::next_command
+/// [`acknowledge`]: Self::acknowledge
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConfigXmlDownload<'a> {
+    index: u8,
+    buf: &'a mut [u8],
+    chunk_size: usize,
+    total_len: Option<usize>,
+    offset: usize,
+}
+
+impl<'a> ConfigXmlDownload<'a> {
+    /// Creates a new download of the XML of the custom config with the given
+    /// index into `buf`.
+    pub fn new(index: u8, buf: &'a mut [u8]) -> Self {
+        Self {
+            index,
+            buf,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            total_len: None,
+            offset: 0,
+        }
+    }
+
+    /// Sets the maximum number of bytes requested in a single chunk.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Returns the total length of the XML, once known.
+    pub fn total_len(&self) -> Option<usize> {
+        self.total_len
+    }
+
+    /// Returns the number of bytes downloaded so far.
+    pub fn downloaded_len(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` once the whole XML has been downloaded.
+    pub fn is_finished(&self) -> bool {
+        self.total_len == Some(self.offset)
+    }
+
+    /// Returns the XML downloaded so far.
+    pub fn xml(&self) -> &[u8] {
+        &self.buf[..self.offset]
+    }
+
+    /// Returns the command for the pending download step, or `None` if the
+    /// download is finished.
+    pub fn next_command(&self) -> Option<Command<'static>> {
+        let len = match self.total_len {
+            None => 0,
+            Some(total_len) if total_len == self.offset => return None,
+            Some(total_len) => self.chunk_size.min(total_len - self.offset),
+        };
+        Some(Command::GetCustomConfigXml(
+            self.index,
+            self.offset as u32,
+            len as u32,
+        ))
+    }
+
+    /// Stores the chunk contained in the reply, and advances the download to
+    /// the next step.
+    ///
+    /// On error the download stays at the pending step, so the same command
+    /// can be retried.
+    pub fn acknowledge<const N: usize>(
+        &mut self,
+        reply: &CommandReply<N>,
+    ) -> Result<(), DownloadError> {
+        let CommandReply::GetCustomConfigXml(chunk) = reply else {
+            return Err(DownloadError::UnexpectedReply);
+        };
+        if chunk.index != self.index || self.is_finished() {
+            return Err(DownloadError::UnexpectedReply);
+        }
+
+        let total_len = chunk.total_len as usize;
+        if total_len > self.buf.len() {
+            return Err(DownloadError::BufferTooSmall {
+                required: chunk.total_len,
+            });
+        }
+        let expected = self.offset as u32;
+        if chunk.offset != expected {
+            return Err(DownloadError::OffsetMismatch {
+                expected,
+                actual: chunk.offset,
+            });
+        }
+        // Only the first chunk, which learns the total length, may be empty
+        // before the end of the XML, as any other would stall the download.
+        let end = self.offset + chunk.data.len();
+        if end > total_len
+            || (chunk.data.is_empty() && end != total_len && self.total_len.is_some())
+            || self.total_len.is_some_and(|len| len != total_len)
+        {
+            return Err(DownloadError::UnexpectedReply);
+        }
+
+        self.buf[self.offset..end].copy_from_slice(&chunk.data);
+        self.offset = end;
+        self.total_len = Some(total_len);
+        Ok(())
+    }
+}
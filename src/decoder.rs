@@ -1,5 +1,12 @@
 use crate::{CommandReply, DecodeError};
 
+/// Default length of the [`Decoder`] buffer, and hence the default capacity of
+/// the data carried by a [`CommandReply`].
+///
+/// This fits a long frame carrying a payload of 512 bytes, the largest the
+/// firmware sends.
+pub const DEFAULT_BUFFER_LEN: usize = 518;
+
 /// A streaming decoder for VESC communication protocol.
 ///
 /// The `Decoder` maintains an internal buffer that accumulates incoming data
@@ -10,16 +17,22 @@ use crate::{CommandReply, DecodeError};
 /// The decoder accepts data via [`feed`] and yields decoded frames through
 /// the [`Iterator`] interface.
 ///
+/// The buffer length `BUFLEN` also sets the capacity of the data carried by
+/// the decoded replies, e.g. [`CommandReply::CustomAppData`], since none of it
+/// can exceed a single frame. It must fit the longest frame expected, i.e. the
+/// payload plus 5 bytes of framing for short frames and 6 bytes for long
+/// frames, as longer frames are dropped.
+///
 /// [`feed`]: Self::feed
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Decoder<const BUFLEN: usize = 512> {
+pub struct Decoder<const BUFLEN: usize = DEFAULT_BUFFER_LEN> {
     buf: [u8; BUFLEN],
     wpos: usize,
     rpos: usize,
 }
 
-impl Default for Decoder<512> {
+impl Default for Decoder<DEFAULT_BUFFER_LEN> {
     fn default() -> Self {
         Self::new()
     }
@@ -71,7 +84,7 @@ impl<const BUFLEN: usize> Decoder<BUFLEN> {
 }
 
 impl<const BUFLEN: usize> core::iter::Iterator for Decoder<BUFLEN> {
-    type Item = CommandReply;
+    type Item = CommandReply<BUFLEN>;
 
     /// Attempts to decode the next complete frame from the internal buffer.
     ///
@@ -80,7 +93,7 @@ impl<const BUFLEN: usize> core::iter::Iterator for Decoder<BUFLEN> {
     /// skipping corrupted data.
     fn next(&mut self) -> Option<Self::Item> {
         while self.rpos < self.wpos {
-            match crate::decode(&self.buf[self.rpos..self.wpos]) {
+            match crate::command::decode_frame(&self.buf[self.rpos..self.wpos]) {
                 Ok((consumed, reply)) => {
                     self.rpos += consumed;
                     return Some(reply);
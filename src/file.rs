@@ -0,0 +1,307 @@
+use crate::{Command, CommandReply, DEFAULT_CHUNK_SIZE, FileWriteParams};
+
+/// Errors that can occur during file transfers.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum FileTransferError {
+    #[error("the controller failed to read the file")]
+    ReadFailed,
+
+    #[error("the controller failed to write the file chunk at offset {offset}")]
+    WriteFailed { offset: u32 },
+
+    #[error("the file of {required} bytes does not fit into the buffer")]
+    BufferTooSmall { required: u32 },
+
+    #[error("offset mismatch: expected {expected}, but found {actual}")]
+    OffsetMismatch { expected: u32, actual: u32 },
+
+    #[error("the reply does not belong to the pending transfer step")]
+    UnexpectedReply,
+
+    #[error("the file does not fit into the 32-bit size of a file write")]
+    FileTooLarge,
+}
+
+/// A state machine for downloading a file from the file system of a VESC
+/// device into a caller-supplied buffer.
+///
+/// Each step yields a [`Command`] via [`next_command`] that must be sent to
+/// the controller, and the received reply must be passed to [`acknowledge`]
+/// before the next step is yielded. The controller chooses the size of each
+/// chunk and reports the size of the file along with it.
+///
+/// An interrupted download can be resumed with [`resume_from`], passing the
+/// [`downloaded_len`] of the previous download and the same buffer.
+///
+/// # Example
+///
+/// ```no_run
+/// use vesc::FileDownload;
+///
+/// # fn send(_: &[u8]) {}
+/// # fn receive() -> vesc::CommandReply { unimplemented!() }
+/// let mut file = [0u8; 4096];
+/// let mut download = FileDownload::new("/log/1.csv", &mut file);
+/// let mut buf = [0u8; 64];
+///
+/// while let Some(command) = download.next_command() {
+///     let len = vesc::encode(command, &mut buf).unwrap();
+///     send(&buf[..len]);
+///     download.acknowledge(&receive()).unwrap();
+/// }
+///
+/// let file = download.data();
+/// ```
+///
+/// [`next_command`]: Self::next_command
+/// [`acknowledge`]: Self::acknowledge
+/// [`resume_from`]: Self::resume_from
+/// [`downloaded_len`]: Self::downloaded_len
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FileDownload<'a> {
+    path: &'a str,
+    buf: &'a mut [u8],
+    size: Option<usize>,
+    offset: usize,
+}
+
+impl<'a> FileDownload<'a> {
+    /// Creates a new download of the file at `path` into `buf`.
+    pub fn new(path: &'a str, buf: &'a mut [u8]) -> Self {
+        Self {
+            path,
+            buf,
+            size: None,
+            offset: 0,
+        }
+    }
+
+    /// Resumes the download at the given offset, keeping the bytes already
+    /// stored in the buffer before it.
+    pub fn resume_from(mut self, offset: usize) -> Self {
+        self.offset = offset.min(self.buf.len());
+        self
+    }
+
+    /// Returns the size of the file, once known.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
+
+    /// Returns the number of bytes downloaded so far.
+    pub fn downloaded_len(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` once the whole file has been downloaded.
+    pub fn is_finished(&self) -> bool {
+        self.size == Some(self.offset)
+    }
+
+    /// Returns the file data downloaded so far.
+    pub fn data(&self) -> &[u8] {
+        &self.buf[..self.offset]
+    }
+
+    /// Returns the command for the pending download step, or `None` if the
+    /// download is finished.
+    pub fn next_command(&self) -> Option<Command<'a>> {
+        if self.is_finished() {
+            return None;
+        }
+        Some(Command::FileRead(self.path, self.offset as u32))
+    }
+
+    /// Stores the chunk contained in the reply, and advances the download to
+    /// the next step.
+    ///
+    /// On error the download stays at the pending step, so the same command
+    /// can be retried.
+    pub fn acknowledge<const N: usize>(
+        &mut self,
+        reply: &CommandReply<N>,
+    ) -> Result<(), FileTransferError> {
+        let CommandReply::FileRead(chunk) = reply else {
+            return Err(FileTransferError::UnexpectedReply);
+        };
+        if self.is_finished() {
+            return Err(FileTransferError::UnexpectedReply);
+        }
+
+        let Ok(size) = usize::try_from(chunk.size) else {
+            return Err(FileTransferError::ReadFailed);
+        };
+        if size > self.buf.len() {
+            return Err(FileTransferError::BufferTooSmall {
+                required: size as u32,
+            });
+        }
+        let expected = self.offset as u32;
+        if chunk.offset != expected {
+            return Err(FileTransferError::OffsetMismatch {
+                expected,
+                actual: chunk.offset,
+            });
+        }
+        // An empty chunk before the end of the file would stall the download.
+        let end = self.offset + chunk.data.len();
+        if end > size
+            || (chunk.data.is_empty() && end != size)
+            || self.size.is_some_and(|len| len != size)
+        {
+            return Err(FileTransferError::UnexpectedReply);
+        }
+
+        self.buf[self.offset..end].copy_from_slice(&chunk.data);
+        self.offset = end;
+        self.size = Some(size);
+        Ok(())
+    }
+}
+
+/// A state machine for uploading a file to the file system of a VESC device.
+///
+/// The file is written in chunks. Each step yields a [`Command`] via
+/// [`next_command`] that must be sent to the controller, and must be
+/// acknowledged by passing the received reply to [`acknowledge`] before the
+/// next step is yielded. An empty file is written with a single empty chunk.
+///
+/// An interrupted upload can be resumed with [`resume_from`], passing the
+/// [`uploaded_len`] of the previous upload.
+///
+/// # Example
+///
+/// ```no_run
+/// use vesc::FileUpload;
+///
+/// # fn send(_: &[u8]) {}
+/// # fn receive() -> vesc::CommandReply { unimplemented!() }
+/// let script = b"(print \"hello\")";
+/// let mut upload = FileUpload::new("/scripts/hello.lisp", script).unwrap();
+/// let mut buf = [0u8; 512];
+///
+/// while let Some(command) = upload.next_command() {
+///     let len = vesc::encode(command, &mut buf).unwrap();
+///     send(&buf[..len]);
+///     upload.acknowledge(&receive()).unwrap();
+/// }
+/// ```
+///
+/// [`next_command`]: Self::next_command
+/// [`acknowledge`]: Self::acknowledge
+/// [`resume_from`]: Self::resume_from
+/// [`uploaded_len`]: Self::uploaded_len
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FileUpload<'a> {
+    path: &'a str,
+    data: &'a [u8],
+    size: u32,
+    chunk_size: usize,
+    offset: usize,
+    finished: bool,
+}
+
+impl<'a> FileUpload<'a> {
+    /// Creates a new upload of `data` to the file at `path`.
+    ///
+    /// Fails with [`FileTransferError::FileTooLarge`] if `data` is larger than
+    /// [`u32::MAX`] bytes.
+    pub fn new(path: &'a str, data: &'a [u8]) -> Result<Self, FileTransferError> {
+        let size = u32::try_from(data.len()).map_err(|_| FileTransferError::FileTooLarge)?;
+        Ok(Self {
+            path,
+            data,
+            size,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            offset: 0,
+            finished: false,
+        })
+    }
+
+    /// Sets the maximum number of bytes sent in a single chunk.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Resumes the upload at the given offset, skipping the bytes before it.
+    pub fn resume_from(mut self, offset: usize) -> Self {
+        self.offset = offset.min(self.data.len());
+        self
+    }
+
+    /// Returns the total number of bytes to upload.
+    pub fn total_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the number of bytes uploaded and acknowledged so far.
+    pub fn uploaded_len(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` once the whole file has been uploaded.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns the command for the pending upload step, or `None` if the
+    /// upload is finished.
+    pub fn next_command(&self) -> Option<Command<'a>> {
+        if self.finished {
+            return None;
+        }
+        Some(Command::FileWrite(FileWriteParams {
+            path: self.path,
+            offset: self.offset(),
+            size: self.size,
+            data: self.next_chunk(),
+        }))
+    }
+
+    /// Verifies that the reply acknowledges the pending upload step, and
+    /// advances the upload to the next step.
+    ///
+    /// On error the upload stays at the pending step, so the same command can
+    /// be retried.
+    pub fn acknowledge<const N: usize>(
+        &mut self,
+        reply: &CommandReply<N>,
+    ) -> Result<(), FileTransferError> {
+        let CommandReply::FileWrite(success, offset) = reply else {
+            return Err(FileTransferError::UnexpectedReply);
+        };
+        if self.finished {
+            return Err(FileTransferError::UnexpectedReply);
+        }
+
+        let expected = self.offset();
+        if *offset != expected {
+            return Err(FileTransferError::OffsetMismatch {
+                expected,
+                actual: *offset,
+            });
+        }
+        if !success {
+            return Err(FileTransferError::WriteFailed { offset: *offset });
+        }
+        self.offset += self.next_chunk().len();
+        self.finished = self.offset == self.data.len();
+        Ok(())
+    }
+
+    /// Returns the offset of the pending chunk, which never exceeds the size
+    /// checked to fit into `u32` on creation.
+    fn offset(&self) -> u32 {
+        u32::try_from(self.offset).unwrap_or(self.size)
+    }
+
+    fn next_chunk(&self) -> &'a [u8] {
+        let end = self.data.len().min(self.offset + self.chunk_size);
+        &self.data[self.offset..end]
+    }
+}
@@ -0,0 +1,325 @@
+use crate::command::CRC16;
+use crate::{Command, CommandReply, DEFAULT_CHUNK_SIZE, HwType, lzo};
+
+/// Length of the header the firmware expects in front of a firmware image:
+/// the image size as `u32` followed by the image CRC16 as `u16`.
+const HEADER_LEN: usize = 6;
+
+/// Errors that can occur during firmware upload.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum UploadError {
+    #[error("the controller failed to erase the flash area for the new firmware")]
+    EraseFailed,
+
+    #[error("the controller failed to write the firmware chunk at offset {offset}")]
+    WriteFailed { offset: u32 },
+
+    #[error("offset mismatch: expected {expected}, but found {actual}")]
+    OffsetMismatch { expected: u32, actual: u32 },
+
+    #[error("the reply does not acknowledge the pending upload step")]
+    UnexpectedReply,
+
+    #[error("the firmware image does not fit into the 32-bit size of the header")]
+    ImageTooLarge,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+enum UploadStep {
+    Erase,
+    Write,
+    Done,
+}
+
+/// A state machine for uploading a firmware image to a VESC controller.
+///
+/// The upload prepends the size and CRC header the firmware expects to the
+/// image, erases the flash area for the new firmware and writes the image in
+/// chunks. Each step yields a [`Command`] via [`next_command`] that must be
+/// sent to the controller, and must be acknowledged by passing the received
+/// reply to [`acknowledge`] before the next step is yielded. Once all steps
+/// are acknowledged, send [`Command::JumpToBootloader`] (or
+/// [`Command::JumpToBootloaderAllCan`]) to flash the uploaded firmware.
+///
+/// Erasing the flash may take several seconds, so the reply to the erase
+/// command should be awaited with a generous timeout.
+///
+/// Over slow links chunks can be LZO1X compressed by providing a scratch
+/// buffer via [`lzo`]. Chunks that do not shrink are sent uncompressed.
+///
+/// On a bus with different kinds of devices the upload can be restricted to
+/// a single hardware type via [`hw`]. In that case send
+/// [`Command::JumpToBootloaderHw`] (or [`Command::JumpToBootloaderAllCanHw`])
+/// to flash the uploaded firmware.
+///
+/// # Example
+///
+/// ```no_run
+/// use vesc::{Command, FirmwareUpload};
+///
+/// # fn send(_: &[u8]) {}
+/// # fn receive() -> vesc::CommandReply { unimplemented!() }
+/// let image = [0u8; 1024];
+/// let mut upload = FirmwareUpload::new(&image).unwrap();
+/// let mut buf = [0u8; 512];
+///
+/// while let Some(command) = upload.next_command() {
+///     let len = vesc::encode(command, &mut buf).unwrap();
+///     send(&buf[..len]);
+///     upload.acknowledge(&receive()).unwrap();
+/// }
+///
+/// let len = vesc::encode(Command::JumpToBootloader, &mut buf).unwrap();
+/// send(&buf[..len]);
+/// ```
+///
+/// [`next_command`]: Self::next_command
+/// [`acknowledge`]: Self::acknowledge
+/// [`lzo`]: Self::lzo
+/// [`hw`]: Self::hw
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FirmwareUpload<'a> {
+    image: &'a [u8],
+    header: [u8; HEADER_LEN],
+    size: u32,
+    chunk_size: usize,
+    all_can: bool,
+    hw_type: Option<HwType>,
+    step: UploadStep,
+    offset: usize,
+    lzo_buf: Option<&'a mut [u8]>,
+    lzo_len: Option<usize>,
+}
+
+impl<'a> FirmwareUpload<'a> {
+    /// Creates a new upload of the given firmware image.
+    ///
+    /// Fails with [`UploadError::ImageTooLarge`] if the image, including the
+    /// header, is larger than [`u32::MAX`] bytes.
+    pub fn new(image: &'a [u8]) -> Result<Self, UploadError> {
+        let image_len = u32::try_from(image.len()).map_err(|_| UploadError::ImageTooLarge)?;
+        let size = image_len
+            .checked_add(HEADER_LEN as u32)
+            .ok_or(UploadError::ImageTooLarge)?;
+
+        let mut header = [0u8; HEADER_LEN];
+        header[..4].copy_from_slice(&image_len.to_be_bytes());
+        header[4..].copy_from_slice(&CRC16.checksum(image).to_be_bytes());
+
+        Ok(Self {
+            image,
+            header,
+            size,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            all_can: false,
+            hw_type: None,
+            step: UploadStep::Erase,
+            offset: 0,
+            lzo_buf: None,
+            lzo_len: None,
+        })
+    }
+
+    /// Sets the maximum number of image bytes sent in a single chunk.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Uploads the firmware to every controller on the CAN bus as well, using
+    /// the `*AllCan` commands.
+    pub fn all_can(mut self) -> Self {
+        self.all_can = true;
+        self
+    }
+
+    /// Uploads the firmware only to devices of the given hardware type, using
+    /// the `*Hw` commands. There are no compressed variants of these commands,
+    /// hence chunks are always sent uncompressed.
+    pub fn hw(mut self, hw_type: HwType) -> Self {
+        self.hw_type = Some(hw_type);
+        self
+    }
+
+    /// Compresses chunks with LZO1X before sending them, using `buf` as scratch
+    /// space for the compressed data. Compressed chunks longer than `buf`
+    /// are sent uncompressed, hence a buffer of the chunk size is sufficient.
+    pub fn lzo(mut self, buf: &'a mut [u8]) -> Self {
+        self.lzo_buf = Some(buf);
+        self
+    }
+
+    /// Returns the total number of bytes to upload, including the header.
+    pub fn total_len(&self) -> usize {
+        HEADER_LEN + self.image.len()
+    }
+
+    /// Returns the number of bytes uploaded and acknowledged so far.
+    pub fn uploaded_len(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` once the whole image has been uploaded.
+    pub fn is_finished(&self) -> bool {
+        self.step == UploadStep::Done
+    }
+
+    /// Returns the command for the pending upload step, or `None` if the
+    /// upload is finished.
+    ///
+    /// The header is sent as a chunk of its own, so that image chunks can be
+    /// borrowed directly from the image.
+    pub fn next_command(&self) -> Option<Command<'_>> {
+        match self.step {
+            UploadStep::Erase => {
+                let size = self.size;
+                Some(match (self.hw_type, self.all_can) {
+                    (Some(hw_type), false) => Command::EraseNewAppHw(hw_type, size),
+                    (Some(hw_type), true) => Command::EraseNewAppAllCanHw(hw_type, size),
+                    (None, false) => Command::EraseNewApp(size),
+                    (None, true) => Command::EraseNewAppAllCan(size),
+                })
+            }
+            UploadStep::Write => {
+                let chunk = self.next_chunk();
+                let offset = self.offset();
+                Some(
+                    match (self.hw_type, self.compressed_chunk(), self.all_can) {
+                        (Some(hw_type), _, false) => {
+                            Command::WriteNewAppDataHw(hw_type, offset, chunk)
+                        }
+                        (Some(hw_type), _, true) => {
+                            Command::WriteNewAppDataAllCanHw(hw_type, offset, chunk)
+                        }
+                        (None, Some(data), false) => {
+                            Command::WriteNewAppDataLzo(offset, self.lzo_chunk_len(), data)
+                        }
+                        (None, Some(data), true) => {
+                            Command::WriteNewAppDataAllCanLzo(offset, self.lzo_chunk_len(), data)
+                        }
+                        (None, None, false) => Command::WriteNewAppData(offset, chunk),
+                        (None, None, true) => Command::WriteNewAppDataAllCan(offset, chunk),
+                    },
+                )
+            }
+            UploadStep::Done => None,
+        }
+    }
+
+    /// Verifies that the reply acknowledges the pending upload step, and
+    /// advances the upload to the next step.
+    ///
+    /// On error the upload stays at the pending step, so the same command can
+    /// be retried.
+    pub fn acknowledge<const N: usize>(
+        &mut self,
+        reply: &CommandReply<N>,
+    ) -> Result<(), UploadError> {
+        match self.step {
+            UploadStep::Erase => {
+                let success = self.erase_result(reply)?;
+                if !success {
+                    return Err(UploadError::EraseFailed);
+                }
+                self.step = UploadStep::Write;
+                self.compress_chunk();
+            }
+            UploadStep::Write => {
+                let (success, offset) = self.write_result(reply)?;
+                let expected = self.offset();
+                if offset != expected {
+                    return Err(UploadError::OffsetMismatch {
+                        expected,
+                        actual: offset,
+                    });
+                }
+                if !success {
+                    return Err(UploadError::WriteFailed { offset });
+                }
+                self.offset += self.next_chunk().len();
+                if self.offset == self.total_len() {
+                    self.step = UploadStep::Done;
+                }
+                self.compress_chunk();
+            }
+            UploadStep::Done => return Err(UploadError::UnexpectedReply),
+        }
+        Ok(())
+    }
+
+    /// Returns the result of the erase step, if the reply belongs to it.
+    fn erase_result<const N: usize>(&self, reply: &CommandReply<N>) -> Result<bool, UploadError> {
+        match (self.hw_type.is_some(), self.all_can, reply) {
+            (false, false, CommandReply::EraseNewApp(success))
+            | (false, true, CommandReply::EraseNewAppAllCan(success))
+            | (true, false, CommandReply::EraseNewAppHw(success))
+            | (true, true, CommandReply::EraseNewAppAllCanHw(success)) => Ok(*success),
+            _ => Err(UploadError::UnexpectedReply),
+        }
+    }
+
+    /// Returns the result of the pending write step, if the reply belongs to
+    /// it.
+    fn write_result<const N: usize>(
+        &self,
+        reply: &CommandReply<N>,
+    ) -> Result<(bool, u32), UploadError> {
+        let is_compressed = self.lzo_len.is_some();
+        match (self.hw_type.is_some(), self.all_can, is_compressed, reply) {
+            (false, false, false, CommandReply::WriteNewAppData(success, offset))
+            | (false, true, false, CommandReply::WriteNewAppDataAllCan(success, offset))
+            | (false, false, true, CommandReply::WriteNewAppDataLzo(success, offset))
+            | (false, true, true, CommandReply::WriteNewAppDataAllCanLzo(success, offset))
+            | (true, false, _, CommandReply::WriteNewAppDataHw(success, offset))
+            | (true, true, _, CommandReply::WriteNewAppDataAllCanHw(success, offset)) => {
+                Ok((*success, *offset))
+            }
+            _ => Err(UploadError::UnexpectedReply),
+        }
+    }
+
+    /// Returns the offset of the pending chunk, which never exceeds the size
+    /// checked to fit into `u32` on creation.
+    fn offset(&self) -> u32 {
+        u32::try_from(self.offset).unwrap_or(self.size)
+    }
+
+    /// Returns the uncompressed length of the pending chunk, which is only
+    /// compressed if its length fits into `u16`.
+    fn lzo_chunk_len(&self) -> u16 {
+        u16::try_from(self.next_chunk().len()).unwrap_or(u16::MAX)
+    }
+
+    fn compressed_chunk(&self) -> Option<&[u8]> {
+        Some(&self.lzo_buf.as_deref()?[..self.lzo_len?])
+    }
+
+    fn compress_chunk(&mut self) {
+        if self.hw_type.is_some() {
+            return;
+        }
+        // The header is never worth compressing, and the uncompressed length
+        // of a compressed chunk is sent as `u16`.
+        self.lzo_len = self
+            .image_chunk()
+            .filter(|chunk| u16::try_from(chunk.len()).is_ok())
+            .and_then(|chunk| {
+                lzo::compress(chunk, self.lzo_buf.as_deref_mut()?).filter(|len| *len < chunk.len())
+            });
+    }
+
+    fn next_chunk(&self) -> &[u8] {
+        self.image_chunk()
+            .unwrap_or_else(|| &self.header[self.offset..])
+    }
+
+    fn image_chunk(&self) -> Option<&'a [u8]> {
+        let pos = self.offset.checked_sub(HEADER_LEN)?;
+        let end = self.image.len().min(pos + self.chunk_size);
+        Some(&self.image[pos..end])
+    }
+}
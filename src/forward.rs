@@ -0,0 +1,223 @@
+use crate::{
+    Command, CommandReply, DEFAULT_BUFFER_LEN, EncodeError, SetupValuesMask, ValuesMask, encode,
+};
+
+/// The controller ID that forwards a command to every controller on the bus.
+pub const CAN_BROADCAST_ID: u8 = 255;
+
+/// A command forwarded over the CAN bus to one or more VESC controllers.
+///
+/// Unlike [`Command::ForwardCan`], the forwarded command is passed by value,
+/// and one frame per target controller is encoded into a single buffer by
+/// [`encode_into`]. Forwarding a [`Command::ForwardCan`] is rejected with
+/// [`EncodeError::NestedForwardCan`], as the firmware does not forward
+/// commands further than one hop.
+///
+/// The target controllers must be given explicitly with [`to`], [`to_all`] or
+/// [`broadcast`].
+///
+/// # Replies
+///
+/// Replies to [`Command::GetValues`] and [`Command::GetValuesSetup`], as well
+/// as to their selective variants if the controller ID is requested, carry
+/// the ID of the controller that sent them, and are tagged with it by
+/// [`tag_replies`], including replies to a broadcast.
+///
+/// Other replies do not carry the ID of their sender. These are paired with
+/// the targets in the order the frames were sent, which is best-effort: if a
+/// controller fails to reply or replies late, every later reply is tagged
+/// with the wrong ID, and replies to a broadcast are tagged with
+/// [`CAN_BROADCAST_ID`]. To attribute such replies reliably, send the frames
+/// of [`split`] one at a time, and wait for each reply before sending the
+/// next one.
+///
+/// # Example
+///
+/// ```no_run
+/// use vesc::{CanForward, Command, Decoder};
+///
+/// # fn send(_: &[u8]) {}
+/// # fn receive(_: &mut Decoder) {}
+/// let forward = CanForward::to_all(&[1, 2, 3], Command::GetValues);
+/// let mut buf = [0u8; 64];
+///
+/// let len = forward.encode_into(&mut buf).unwrap();
+/// send(&buf[..len]);
+///
+/// let mut decoder = Decoder::default();
+/// receive(&mut decoder);
+///
+/// for tagged in forward.tag_replies(decoder.by_ref()) {
+///     let controller_id = tagged.controller_id;
+///     let reply = tagged.reply;
+/// }
+/// ```
+///
+/// [`encode_into`]: Self::encode_into
+/// [`to`]: Self::to
+/// [`to_all`]: Self::to_all
+/// [`broadcast`]: Self::broadcast
+/// [`tag_replies`]: Self::tag_replies
+/// [`split`]: Self::split
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CanForward<'a> {
+    command: Command<'a>,
+    target: u8,
+    targets: Option<&'a [u8]>,
+}
+
+impl<'a> CanForward<'a> {
+    /// Forwards `command` to the controller with the given ID.
+    pub fn to(controller_id: u8, command: Command<'a>) -> Self {
+        Self {
+            command,
+            target: controller_id,
+            targets: None,
+        }
+    }
+
+    /// Forwards `command` to each controller in `controller_ids`, in order.
+    pub fn to_all(controller_ids: &'a [u8], command: Command<'a>) -> Self {
+        Self {
+            command,
+            target: CAN_BROADCAST_ID,
+            targets: Some(controller_ids),
+        }
+    }
+
+    /// Forwards `command` to every controller on the bus with a single frame.
+    pub fn broadcast(command: Command<'a>) -> Self {
+        Self::to(CAN_BROADCAST_ID, command)
+    }
+
+    /// Returns the forwarded command.
+    pub fn command(&self) -> &Command<'a> {
+        &self.command
+    }
+
+    /// Returns the IDs of the target controllers, in the order the frames are
+    /// encoded.
+    pub fn targets(&self) -> &[u8] {
+        self.targets.unwrap_or(core::slice::from_ref(&self.target))
+    }
+
+    /// Returns one forward per target controller, in the order the frames are
+    /// encoded.
+    pub fn split(&self) -> impl Iterator<Item = CanForward<'a>> + '_ {
+        let command = self.command;
+        self.targets()
+            .iter()
+            .map(move |&controller_id| Self::to(controller_id, command))
+    }
+
+    /// Encodes one frame per target controller into `buf`, back to back.
+    ///
+    /// Returns the total number of bytes written on success. Nothing is
+    /// written if the forwarded command is a [`Command::ForwardCan`].
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        if let Command::ForwardCan(..) = self.command {
+            return Err(EncodeError::NestedForwardCan);
+        }
+
+        let mut pos = 0;
+        for &controller_id in self.targets() {
+            let frame = buf.get_mut(pos..).ok_or(EncodeError::BufferTooSmall)?;
+            pos += encode(Command::ForwardCan(controller_id, &self.command), frame)?;
+        }
+        Ok(pos)
+    }
+
+    /// Tags `reply` with the ID of the controller it originates from.
+    ///
+    /// The ID is taken from the reply if it carries one. Otherwise, the reply
+    /// is tagged with the target controller, unless the command has been
+    /// forwarded to several controllers or broadcast, in which case `None` is
+    /// returned.
+    pub fn tag_reply<const N: usize>(&self, reply: CommandReply<N>) -> Option<TaggedReply<N>> {
+        let controller_id = match (reply_controller_id(&self.command, &reply), self.targets()) {
+            (Some(controller_id), _) => controller_id,
+            (None, &[controller_id]) if controller_id != CAN_BROADCAST_ID => controller_id,
+            (None, _) => return None,
+        };
+        Some(TaggedReply {
+            controller_id,
+            reply,
+        })
+    }
+
+    /// Pairs each of `replies` with the ID of the controller it originates
+    /// from.
+    ///
+    /// The ID is taken from the reply if it carries one. Otherwise, the
+    /// replies are assumed to arrive in the order the frames were sent, which
+    /// is best-effort, see [Replies](Self#replies).
+    ///
+    /// The iterator ends once there has been a reply per target, except for a
+    /// broadcast, where replies are tagged until `replies` is exhausted.
+    pub fn tag_replies<const N: usize, I>(&self, replies: I) -> TaggedReplies<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = CommandReply<N>>,
+    {
+        TaggedReplies {
+            command: &self.command,
+            targets: self.targets(),
+            replies: replies.into_iter(),
+        }
+    }
+}
+
+/// Returns the ID of the controller that sent `reply` to `command`, if the
+/// reply carries one.
+fn reply_controller_id<const N: usize>(command: &Command, reply: &CommandReply<N>) -> Option<u8> {
+    match (command, reply) {
+        (Command::GetValues, CommandReply::GetValues(values)) => Some(values.controller_id),
+        (Command::GetValuesSelective(mask), CommandReply::GetValuesSelective(values))
+            if mask.contains(ValuesMask::CONTROLLER_ID) =>
+        {
+            Some(values.controller_id)
+        }
+        (Command::GetValuesSetup, CommandReply::GetValuesSetup(values)) => {
+            Some(values.controller_id)
+        }
+        (Command::GetValuesSetupSelective(mask), CommandReply::GetValuesSetupSelective(values))
+            if mask.contains(SetupValuesMask::CONTROLLER_ID) =>
+        {
+            Some(values.controller_id)
+        }
+        _ => None,
+    }
+}
+
+/// A reply paired with the ID of the controller it originates from.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TaggedReply<const N: usize = DEFAULT_BUFFER_LEN> {
+    pub controller_id: u8,
+    pub reply: CommandReply<N>,
+}
+
+/// An iterator over replies tagged with their originating controller ID,
+/// created by [`CanForward::tag_replies`].
+#[derive(Debug)]
+pub struct TaggedReplies<'a, I> {
+    command: &'a Command<'a>,
+    targets: &'a [u8],
+    replies: I,
+}
+
+impl<const N: usize, I: Iterator<Item = CommandReply<N>>> Iterator for TaggedReplies<'_, I> {
+    type Item = TaggedReply<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&target, rest) = self.targets.split_first()?;
+        let reply = self.replies.next()?;
+        if self.targets != [CAN_BROADCAST_ID] {
+            self.targets = rest;
+        }
+        Some(TaggedReply {
+            controller_id: reply_controller_id(self.command, &reply).unwrap_or(target),
+            reply,
+        })
+    }
+}
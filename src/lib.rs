@@ -50,19 +50,82 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod command;
+mod config;
 mod decoder;
+mod file;
+mod firmware;
+mod forward;
+mod lzo;
 mod packer;
+#[cfg(feature = "std")]
+mod schema;
 
 pub use command::{
     //
+    ApplyAllFocParams,
+    BMS_MAX_CELLS,
+    BMS_MAX_TEMPS,
+    BatteryCutParams,
+    BldcDetection,
+    BmsValues,
+    CanBaud,
+    CanFrame,
+    CanMode,
+    ChuckData,
     Command,
     CommandReply,
+    ConfigXmlChunk,
+    DEFAULT_CHUNK_SIZE,
     DecodeError,
+    DecodedAdc,
+    DecodedBalance,
+    DecodedPpm,
+    EXPERIMENT_MAX_SAMPLES,
     EncodeError,
+    EncoderDetection,
+    ExperimentSamples,
     FaultCode,
+    FileChunk,
+    FileEntries,
+    FileEntry,
+    FileList,
+    FileWriteParams,
+    FluxLinkageDetection,
+    FluxLinkageOpenloopParams,
+    FluxLinkageParams,
+    GnssData,
+    GnssMask,
+    HallDetection,
+    HwType,
+    ImuCalibration,
+    ImuData,
+    ImuMask,
+    LispBindings,
+    LispStats,
+    LogField,
+    LogSamples,
+    LogSamplesIter,
+    LogStartParams,
+    MotorSample,
+    PlotAxisNames,
+    ReplyData,
+    ResistanceInductance,
+    SamplingMode,
+    SetupValues,
+    SetupValuesMask,
+    StatsMask,
+    StatsValues,
     Values,
     ValuesMask,
     decode,
     encode,
 };
-pub use decoder::Decoder;
+pub use config::{ConfigXmlDownload, DownloadError};
+pub use decoder::{DEFAULT_BUFFER_LEN, Decoder};
+pub use file::{FileDownload, FileTransferError, FileUpload};
+pub use firmware::{FirmwareUpload, UploadError};
+pub use forward::{CAN_BROADCAST_ID, CanForward, TaggedReplies, TaggedReply};
+#[cfg(feature = "std")]
+pub use schema::{
+    ConfigError, ConfigParam, ConfigSchema, ConfigValue, ConfigValues, ParamType, TxType,
+};
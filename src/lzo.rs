@@ -0,0 +1,143 @@
+//! A minimal LZO1X compressor.
+//!
+//! The VESC firmware decompresses firmware chunks sent with
+//! [`Command::WriteNewAppDataLzo`] using `lzo1x_decompress_safe()`. This module
+//! implements the compressing counterpart without any allocation. It emits a
+//! valid LZO1X-1 stream, but uses a simpler match finder than the reference
+//! implementation and thus trades some compression ratio for code size.
+//!
+//! [`Command::WriteNewAppDataLzo`]: crate::Command::WriteNewAppDataLzo
+
+const DICT_BITS: u32 = 11;
+const MIN_MATCH_LEN: usize = 3;
+const M2_MAX_LEN: usize = 8;
+const M2_MAX_OFFSET: usize = 0x0800;
+const M3_MAX_LEN: usize = 33;
+const M3_MAX_OFFSET: usize = 0x4000;
+const M3_MARKER: u8 = 32;
+const M4_MARKER: u8 = 16;
+
+/// Compresses `input` into `output` using the LZO1X format.
+///
+/// Returns the length of the compressed data, or `None` if it doesn't fit into
+/// `output` or if `input` is longer than 65535 bytes.
+pub(crate) fn compress(input: &[u8], output: &mut [u8]) -> Option<usize> {
+    if input.len() > u16::MAX as usize {
+        return None;
+    }
+
+    let mut writer = Writer {
+        buf: output,
+        pos: 0,
+        state_pos: None,
+    };
+    // Positions are stored incremented by one, so that zero marks an empty
+    // slot.
+    let mut dict = [0u16; 1 << DICT_BITS];
+    let mut literal_start = 0;
+    let mut ip = 0;
+
+    while ip + MIN_MATCH_LEN <= input.len() {
+        let key = &input[ip..ip + MIN_MATCH_LEN];
+        let slot = &mut dict[hash(key)];
+        let candidate = (*slot as usize).checked_sub(1);
+        *slot = (ip + 1) as u16;
+
+        let Some(candidate) = candidate else {
+            ip += 1;
+            continue;
+        };
+        let offset = ip - candidate;
+        if offset > M3_MAX_OFFSET || &input[candidate..candidate + MIN_MATCH_LEN] != key {
+            ip += 1;
+            continue;
+        }
+
+        let len = MIN_MATCH_LEN
+            + input[ip + MIN_MATCH_LEN..]
+                .iter()
+                .zip(&input[candidate + MIN_MATCH_LEN..])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+        writer.literals(&input[literal_start..ip])?;
+        writer.matched(len, offset)?;
+        ip += len;
+        literal_start = ip;
+    }
+
+    writer.literals(&input[literal_start..])?;
+    writer.bytes(&[M4_MARKER | 1, 0, 0])?;
+    Some(writer.pos)
+}
+
+#[inline]
+fn hash(key: &[u8]) -> usize {
+    let value = u32::from(key[0]) | u32::from(key[1]) << 8 | u32::from(key[2]) << 16;
+    (value.wrapping_mul(0x9E37_79B1) >> (32 - DICT_BITS)) as usize
+}
+
+struct Writer<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+    /// Position of the byte whose two low bits hold the number of literals
+    /// that follow the last match, if any.
+    state_pos: Option<usize>,
+}
+
+impl Writer<'_> {
+    fn literals(&mut self, literals: &[u8]) -> Option<()> {
+        let len = literals.len();
+        match (self.state_pos, len) {
+            (_, 0) => (),
+            (None, 1..=238) if self.pos == 0 => self.byte(17 + len as u8)?,
+            (Some(state_pos), 1..=3) => *self.buf.get_mut(state_pos)? |= len as u8,
+            (_, 1..=18) => self.byte(len as u8 - 3)?,
+            _ => {
+                self.byte(0)?;
+                self.length(len - 18)?;
+            }
+        }
+        self.bytes(literals)
+    }
+
+    fn matched(&mut self, len: usize, offset: usize) -> Option<()> {
+        let distance = offset - 1;
+        if len <= M2_MAX_LEN && offset <= M2_MAX_OFFSET {
+            self.state_pos = Some(self.pos);
+            self.bytes(&[
+                ((len - 1) << 5 | (distance & 7) << 2) as u8,
+                (distance >> 3) as u8,
+            ])
+        } else {
+            if len <= M3_MAX_LEN {
+                self.byte(M3_MARKER | (len - 2) as u8)?;
+            } else {
+                self.byte(M3_MARKER)?;
+                self.length(len - M3_MAX_LEN)?;
+            }
+            self.state_pos = Some(self.pos);
+            self.bytes(&[((distance & 63) << 2) as u8, (distance >> 6) as u8])
+        }
+    }
+
+    fn length(&mut self, mut len: usize) -> Option<()> {
+        while len > 255 {
+            self.byte(0)?;
+            len -= 255;
+        }
+        self.byte(len as u8)
+    }
+
+    fn byte(&mut self, byte: u8) -> Option<()> {
+        self.bytes(&[byte])
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> Option<()> {
+        self.buf
+            .get_mut(self.pos..self.pos + bytes.len())?
+            .copy_from_slice(bytes);
+        self.pos += bytes.len();
+        Some(())
+    }
+}
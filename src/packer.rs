@@ -31,6 +31,11 @@ impl<'a> Packer<'a> {
         self.pack(&value.to_be_bytes())
     }
 
+    #[inline]
+    pub fn pack_i16(&mut self, value: i16) -> Result<(), EncodeError> {
+        self.pack(&value.to_be_bytes())
+    }
+
     #[inline]
     pub fn pack_u8(&mut self, value: u8) -> Result<(), EncodeError> {
         self.pack(&value.to_be_bytes())
@@ -41,6 +46,25 @@ impl<'a> Packer<'a> {
         self.pack_i32((value * scale) as i32)
     }
 
+    /// Packs a float the way `buffer_append_float32_auto()` of the VESC
+    /// firmware does, which is bit-compatible with IEEE 754 single precision.
+    #[inline]
+    pub fn pack_f32_auto(&mut self, value: f32) -> Result<(), EncodeError> {
+        self.pack_u32(value.to_bits())
+    }
+
+    #[inline]
+    pub fn pack_bytes(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        self.pack(bytes)
+    }
+
+    /// Packs a null-terminated string.
+    #[inline]
+    pub fn pack_str(&mut self, value: &str) -> Result<(), EncodeError> {
+        self.pack(value.as_bytes())?;
+        self.pack_u8(0)
+    }
+
     #[inline]
     fn pack(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
         let need = bytes.len();
@@ -74,6 +98,11 @@ impl<'a> Unpacker<'a> {
         Ok(u32::from_be_bytes(self.consume(4)?.try_into().unwrap()))
     }
 
+    #[inline]
+    pub fn unpack_i64(&mut self) -> Result<i64, DecodeError> {
+        Ok(i64::from_be_bytes(self.consume(8)?.try_into().unwrap()))
+    }
+
     #[inline]
     pub fn unpack_i32(&mut self) -> Result<i32, DecodeError> {
         Ok(i32::from_be_bytes(self.consume(4)?.try_into().unwrap()))
@@ -94,16 +123,83 @@ impl<'a> Unpacker<'a> {
         Ok(u8::from_be_bytes(self.consume(1)?.try_into().unwrap()))
     }
 
+    #[inline]
+    pub fn unpack_f64(&mut self, scale: f64) -> Result<f64, DecodeError> {
+        Ok(self.unpack_i64()? as f64 / scale)
+    }
+
     #[inline]
     pub fn unpack_f32(&mut self, scale: f32) -> Result<f32, DecodeError> {
         Ok(self.unpack_i32()? as f32 / scale)
     }
 
+    /// Unpacks a float encoded with `buffer_append_float32_auto()` of the VESC
+    /// firmware, which is bit-compatible with IEEE 754 single precision.
+    #[inline]
+    pub fn unpack_f32_auto(&mut self) -> Result<f32, DecodeError> {
+        Ok(f32::from_bits(self.unpack_u32()?))
+    }
+
+    /// Unpacks a double encoded with `buffer_append_float64_auto()` of the VESC
+    /// firmware, i.e. as the nearest single precision float followed by the
+    /// rounding error.
+    #[inline]
+    pub fn unpack_f64_auto(&mut self) -> Result<f64, DecodeError> {
+        let value = self.unpack_f32_auto()?;
+        let error = self.unpack_f32_auto()?;
+        Ok(f64::from(value) + f64::from(error))
+    }
+
     #[inline]
     pub fn unpack_f16(&mut self, scale: f32) -> Result<f32, DecodeError> {
         Ok(self.unpack_i16()? as f32 / scale)
     }
 
+    #[inline]
+    pub fn unpack_i8(&mut self) -> Result<i8, DecodeError> {
+        Ok(i8::from_be_bytes(self.consume(1)?.try_into().unwrap()))
+    }
+
+    #[inline]
+    pub fn unpack_bool(&mut self) -> Result<bool, DecodeError> {
+        Ok(self.unpack_u8()? != 0)
+    }
+
+    #[inline]
+    pub fn unpack_bytes(&mut self, amount: usize) -> Result<&'a [u8], DecodeError> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + amount)
+            .ok_or(DecodeError::IncompleteData)?;
+        self.pos += amount;
+        Ok(bytes)
+    }
+
+    #[inline]
+    pub fn has_remaining(&self) -> bool {
+        self.pos < self.buf.len()
+    }
+
+    /// Unpacks a null-terminated string.
+    #[inline]
+    pub fn unpack_str(&mut self) -> Result<&'a str, DecodeError> {
+        let len = self.buf[self.pos..]
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or(DecodeError::IncompleteData)?;
+        let value = core::str::from_utf8(&self.buf[self.pos..self.pos + len])
+            .map_err(|_| DecodeError::InvalidFrame)?;
+        self.pos += len + 1;
+        Ok(value)
+    }
+
+    #[inline]
+    pub fn unpack_remaining(&mut self) -> &'a [u8] {
+        let bytes = &self.buf[self.pos..];
+        self.pos = self.buf.len();
+        bytes
+    }
+
     #[inline]
     fn consume(&mut self, amount: usize) -> Result<&[u8], DecodeError> {
         self.buf
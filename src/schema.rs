@@ -0,0 +1,544 @@
+use crate::DecodeError;
+use crate::packer::Unpacker;
+
+/// Errors that can occur while parsing a parameter XML or while decoding and
+/// encoding config values with it.
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ConfigError {
+    #[error("malformed XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+
+    #[error("the XML does not describe config parameters")]
+    MissingParams,
+
+    #[error("the parameter {name:?} has an invalid definition")]
+    InvalidParam { name: String },
+
+    #[error("the parameter {name:?} is not described by the schema")]
+    UnknownParam { name: String },
+
+    #[error("the value does not match the type of the parameter {name:?}")]
+    TypeMismatch { name: String },
+
+    #[error("the value of the parameter {name:?} does not fit its encoding")]
+    ValueOutOfRange { name: String },
+
+    #[error("failed to decode the config: {0}")]
+    Decode(#[from] DecodeError),
+
+    #[error("the config blob has {len} unexpected trailing bytes")]
+    TrailingData { len: usize },
+}
+
+/// The type of a config parameter, i.e. `CFG_T_*` in VESC Tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ParamType {
+    Double = 1,
+    Int = 2,
+    String = 3,
+    Enum = 4,
+    Bool = 5,
+    Bitfield = 6,
+}
+
+impl TryFrom<u8> for ParamType {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use ParamType::*;
+
+        match value {
+            v if v == Double as u8 => Ok(Double),
+            v if v == Int as u8 => Ok(Int),
+            v if v == String as u8 => Ok(String),
+            v if v == Enum as u8 => Ok(Enum),
+            v if v == Bool as u8 => Ok(Bool),
+            v if v == Bitfield as u8 => Ok(Bitfield),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The wire encoding of a config parameter, i.e. `VESC_TX_*` in VESC Tool.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TxType {
+    #[default]
+    Undefined = 0,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    Double16,
+    Double32,
+    Double32Auto,
+}
+
+impl TryFrom<u8> for TxType {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use TxType::*;
+
+        match value {
+            v if v == Undefined as u8 => Ok(Undefined),
+            v if v == U8 as u8 => Ok(U8),
+            v if v == I8 as u8 => Ok(I8),
+            v if v == U16 as u8 => Ok(U16),
+            v if v == I16 as u8 => Ok(I16),
+            v if v == U32 as u8 => Ok(U32),
+            v if v == I32 as u8 => Ok(I32),
+            v if v == Double16 as u8 => Ok(Double16),
+            v if v == Double32 as u8 => Ok(Double32),
+            v if v == Double32Auto as u8 => Ok(Double32Auto),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TxType {
+    /// Returns the range of integers representable by the encoding, or `None`
+    /// for floating-point encodings.
+    fn int_range(self) -> Option<(i64, i64)> {
+        match self {
+            TxType::U8 => Some((u8::MIN.into(), u8::MAX.into())),
+            TxType::I8 => Some((i8::MIN.into(), i8::MAX.into())),
+            TxType::U16 => Some((u16::MIN.into(), u16::MAX.into())),
+            TxType::I16 => Some((i16::MIN.into(), i16::MAX.into())),
+            TxType::U32 => Some((u32::MIN.into(), u32::MAX.into())),
+            TxType::I32 => Some((i32::MIN.into(), i32::MAX.into())),
+            _ => None,
+        }
+    }
+}
+
+/// The value of a config parameter.
+///
+/// Enum and bitfield parameters hold [`ConfigValue::Int`] values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    Double(f64),
+    Int(i64),
+    Bool(bool),
+    String(String),
+}
+
+impl ConfigValue {
+    /// Returns the value as `f64`, if it is a double.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ConfigValue::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `i64`, if it is an integer, enum or bitfield.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ConfigValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `bool`, if it is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ConfigValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `&str`, if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ConfigValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// The description of a single config parameter.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ConfigParam {
+    pub name: String,
+    pub long_name: String,
+    pub description: String,
+    pub param_type: ParamType,
+    pub tx_type: TxType,
+    /// The factor a double is multiplied by before it's encoded.
+    pub scale: f64,
+    pub suffix: String,
+    /// Names of enum values, or of bits for bitfields.
+    pub enum_names: Vec<String>,
+    pub default: ConfigValue,
+}
+
+impl ConfigParam {
+    fn from_node(node: roxmltree::Node) -> Result<Self, ConfigError> {
+        let name = node.tag_name().name();
+        let invalid = || ConfigError::InvalidParam { name: name.into() };
+        let text = |tag: &str| {
+            node.children()
+                .find(|child| child.has_tag_name(tag))
+                .map(|child| child.text().unwrap_or_default())
+        };
+        let parse_int = |tag: &str| -> Result<i64, ConfigError> {
+            text(tag)
+                .unwrap_or("0")
+                .trim()
+                .parse()
+                .map_err(|_| invalid())
+        };
+        let parse_double = |tag: &str, default: &str| -> Result<f64, ConfigError> {
+            text(tag)
+                .unwrap_or(default)
+                .trim()
+                .parse()
+                .map_err(|_| invalid())
+        };
+
+        let param_type = u8::try_from(parse_int("type")?)
+            .ok()
+            .and_then(|value| ParamType::try_from(value).ok())
+            .ok_or_else(invalid)?;
+        let tx_type = u8::try_from(parse_int("vTx")?)
+            .ok()
+            .and_then(|value| TxType::try_from(value).ok())
+            .ok_or_else(invalid)?;
+        let scale = parse_double("vTxDoubleScale", "1")?;
+
+        let default = match param_type {
+            ParamType::Double => ConfigValue::Double(parse_double("valDouble", "0")?),
+            ParamType::Int | ParamType::Enum | ParamType::Bitfield => {
+                ConfigValue::Int(parse_int("valInt")?)
+            }
+            ParamType::Bool => ConfigValue::Bool(parse_int("valInt")? != 0),
+            ParamType::String => ConfigValue::String(text("valString").unwrap_or("").into()),
+        };
+
+        // Only doubles may use floating-point encodings, and they need a
+        // usable scale when encoded as integers.
+        let valid = match param_type {
+            ParamType::Double => scale.is_finite() && scale != 0.0,
+            ParamType::String => true,
+            _ => tx_type.int_range().is_some() || tx_type == TxType::Undefined,
+        };
+        if !valid {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            name: name.into(),
+            long_name: text("longName").unwrap_or("").into(),
+            description: text("description").unwrap_or("").into(),
+            param_type,
+            tx_type,
+            scale,
+            suffix: text("suffix").unwrap_or("").into(),
+            enum_names: node
+                .children()
+                .filter(|child| child.has_tag_name("enumNames"))
+                .map(|child| child.text().unwrap_or_default().into())
+                .collect(),
+            default,
+        })
+    }
+
+    /// Returns `true` if the value is of the type of this parameter.
+    fn accepts(&self, value: &ConfigValue) -> bool {
+        matches!(
+            (self.param_type, value),
+            (ParamType::Double, ConfigValue::Double(_))
+                | (
+                    ParamType::Int | ParamType::Enum | ParamType::Bitfield,
+                    ConfigValue::Int(_)
+                )
+                | (ParamType::Bool, ConfigValue::Bool(_))
+                | (ParamType::String, ConfigValue::String(_))
+        )
+    }
+
+    fn unpack(&self, unpacker: &mut Unpacker) -> Result<ConfigValue, DecodeError> {
+        let int = |unpacker: &mut Unpacker| -> Result<i64, DecodeError> {
+            Ok(match self.tx_type {
+                TxType::I8 => unpacker.unpack_i8()?.into(),
+                TxType::U16 => unpacker.unpack_u16()?.into(),
+                TxType::I16 => unpacker.unpack_i16()?.into(),
+                TxType::U32 => unpacker.unpack_u32()?.into(),
+                TxType::I32 => unpacker.unpack_i32()?.into(),
+                _ => unpacker.unpack_u8()?.into(),
+            })
+        };
+
+        Ok(match self.param_type {
+            ParamType::Double => ConfigValue::Double(match self.tx_type {
+                TxType::Double16 => f64::from(unpacker.unpack_i16()?) / self.scale,
+                TxType::Double32 => f64::from(unpacker.unpack_i32()?) / self.scale,
+                TxType::Double32Auto => unpacker.unpack_f32_auto()?.into(),
+                _ => int(unpacker)? as f64 / self.scale,
+            }),
+            ParamType::Int | ParamType::Enum | ParamType::Bitfield => {
+                ConfigValue::Int(int(unpacker)?)
+            }
+            ParamType::Bool => ConfigValue::Bool(int(unpacker)? != 0),
+            ParamType::String => ConfigValue::String(unpacker.unpack_str()?.into()),
+        })
+    }
+
+    fn pack(&self, value: &ConfigValue, buf: &mut Vec<u8>) -> Result<(), ConfigError> {
+        let out_of_range = || ConfigError::ValueOutOfRange {
+            name: self.name.clone(),
+        };
+        let int = |value: i64, buf: &mut Vec<u8>| -> Result<(), ConfigError> {
+            let tx_type = match self.tx_type {
+                TxType::Undefined => TxType::U8,
+                tx_type => tx_type,
+            };
+            let (min, max) = tx_type.int_range().ok_or_else(out_of_range)?;
+            if value < min || value > max {
+                return Err(out_of_range());
+            }
+            match tx_type {
+                TxType::U8 | TxType::I8 => buf.push(value as u8),
+                TxType::U16 | TxType::I16 => buf.extend((value as u16).to_be_bytes()),
+                _ => buf.extend((value as u32).to_be_bytes()),
+            }
+            Ok(())
+        };
+        let scaled = |value: f64, min: f64, max: f64| -> Result<i64, ConfigError> {
+            let value = (value * self.scale).round();
+            if value.is_nan() || value < min || value > max {
+                return Err(out_of_range());
+            }
+            Ok(value as i64)
+        };
+
+        match (self.param_type, value) {
+            (ParamType::Double, ConfigValue::Double(value)) => match self.tx_type {
+                TxType::Double16 => {
+                    let value = scaled(*value, i16::MIN.into(), i16::MAX.into())?;
+                    buf.extend((value as i16).to_be_bytes());
+                }
+                TxType::Double32 => {
+                    let value = scaled(*value, i32::MIN.into(), i32::MAX.into())?;
+                    buf.extend((value as i32).to_be_bytes());
+                }
+                TxType::Double32Auto => buf.extend((*value as f32).to_bits().to_be_bytes()),
+                _ => int(scaled(*value, i64::MIN as f64, i64::MAX as f64)?, buf)?,
+            },
+            (ParamType::Int | ParamType::Enum | ParamType::Bitfield, ConfigValue::Int(value)) => {
+                int(*value, buf)?
+            }
+            (ParamType::Bool, ConfigValue::Bool(value)) => int((*value).into(), buf)?,
+            (ParamType::String, ConfigValue::String(value)) => {
+                if value.contains('\0') {
+                    return Err(out_of_range());
+                }
+                buf.extend(value.as_bytes());
+                buf.push(0);
+            }
+            _ => {
+                return Err(ConfigError::TypeMismatch {
+                    name: self.name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A description of a config layout, parsed from the parameter XML used by
+/// VESC Tool, e.g. the one returned by [`Command::GetCustomConfigXml`] once
+/// decompressed.
+///
+/// Only the parameters listed in the serialization order are part of the
+/// serialized config, though all parameters are available via [`param`].
+///
+/// # Example
+///
+/// ```no_run
+/// use vesc::{ConfigSchema, ConfigValues};
+///
+/// # let xml = "";
+/// # let blob = [];
+/// let schema = ConfigSchema::parse(xml).unwrap();
+/// let values = ConfigValues::decode(&schema, &blob).unwrap();
+/// let current_max = values.get("l_current_max").and_then(|value| value.as_f64());
+/// ```
+///
+/// [`Command::GetCustomConfigXml`]: crate::Command::GetCustomConfigXml
+/// [`param`]: Self::param
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigSchema {
+    params: Vec<ConfigParam>,
+    serialization_order: Vec<usize>,
+}
+
+impl ConfigSchema {
+    /// Parses the parameter XML.
+    pub fn parse(xml: &str) -> Result<Self, ConfigError> {
+        let document = roxmltree::Document::parse(xml)?;
+        let root = document.root_element();
+        if !root.has_tag_name("ConfigParams") {
+            return Err(ConfigError::MissingParams);
+        }
+        let element = |tag: &str| root.children().find(|child| child.has_tag_name(tag));
+
+        let params = element("Params")
+            .ok_or(ConfigError::MissingParams)?
+            .children()
+            .filter(|child| child.is_element())
+            .map(ConfigParam::from_node)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut schema = Self {
+            params,
+            serialization_order: Vec::new(),
+        };
+        if let Some(order) = element("SerOrder") {
+            for ser in order.children().filter(|child| child.has_tag_name("ser")) {
+                let name = ser.text().unwrap_or_default().trim();
+                let index = schema.index_of(name)?;
+                schema.serialization_order.push(index);
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Returns the description of the parameter with the given name.
+    pub fn param(&self, name: &str) -> Option<&ConfigParam> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Returns all parameters in the order they are described in the XML.
+    pub fn params(&self) -> impl Iterator<Item = &ConfigParam> {
+        self.params.iter()
+    }
+
+    /// Returns the parameters of the serialized config in the order they are
+    /// serialized.
+    pub fn serialized_params(&self) -> impl Iterator<Item = &ConfigParam> {
+        self.serialization_order
+            .iter()
+            .map(|&index| &self.params[index])
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize, ConfigError> {
+        self.params
+            .iter()
+            .position(|param| param.name == name)
+            .ok_or_else(|| ConfigError::UnknownParam { name: name.into() })
+    }
+}
+
+/// The values of a serialized config, decoded according to a
+/// [`ConfigSchema`].
+///
+/// A serialized config starts with a signature identifying the layout,
+/// followed by the values in serialization order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigValues<'a> {
+    schema: &'a ConfigSchema,
+    signature: u32,
+    values: Vec<ConfigValue>,
+}
+
+impl<'a> ConfigValues<'a> {
+    /// Creates values holding the defaults described by the schema.
+    pub fn with_defaults(schema: &'a ConfigSchema, signature: u32) -> Self {
+        Self {
+            schema,
+            signature,
+            values: schema
+                .serialized_params()
+                .map(|param| param.default.clone())
+                .collect(),
+        }
+    }
+
+    /// Decodes a serialized config, e.g. the one returned by
+    /// [`Command::GetCustomConfig`].
+    ///
+    /// [`Command::GetCustomConfig`]: crate::Command::GetCustomConfig
+    pub fn decode(schema: &'a ConfigSchema, blob: &[u8]) -> Result<Self, ConfigError> {
+        let mut unpacker = Unpacker::new(blob);
+        let signature = unpacker.unpack_u32()?;
+        let values = schema
+            .serialized_params()
+            .map(|param| param.unpack(&mut unpacker))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let trailing = unpacker.unpack_remaining();
+        if !trailing.is_empty() {
+            return Err(ConfigError::TrailingData {
+                len: trailing.len(),
+            });
+        }
+        Ok(Self {
+            schema,
+            signature,
+            values,
+        })
+    }
+
+    /// Encodes the values into a serialized config, e.g. to be sent with
+    /// [`Command::SetCustomConfig`].
+    ///
+    /// [`Command::SetCustomConfig`]: crate::Command::SetCustomConfig
+    pub fn encode(&self) -> Result<Vec<u8>, ConfigError> {
+        let mut buf = self.signature.to_be_bytes().to_vec();
+        for (param, value) in self.schema.serialized_params().zip(&self.values) {
+            param.pack(value, &mut buf)?;
+        }
+        Ok(buf)
+    }
+
+    /// Returns the signature of the config layout.
+    pub fn signature(&self) -> u32 {
+        self.signature
+    }
+
+    /// Returns the schema the values are described by.
+    pub fn schema(&self) -> &'a ConfigSchema {
+        self.schema
+    }
+
+    /// Returns the value of the parameter with the given name, or `None` if
+    /// the parameter is not part of the serialized config.
+    pub fn get(&self, name: &str) -> Option<&ConfigValue> {
+        let position = self.position(name)?;
+        self.values.get(position)
+    }
+
+    /// Sets the value of the parameter with the given name.
+    pub fn set(&mut self, name: &str, value: ConfigValue) -> Result<(), ConfigError> {
+        let position = self
+            .position(name)
+            .ok_or_else(|| ConfigError::UnknownParam { name: name.into() })?;
+        let index = self.schema.serialization_order[position];
+        if !self.schema.params[index].accepts(&value) {
+            return Err(ConfigError::TypeMismatch { name: name.into() });
+        }
+        self.values[position] = value;
+        Ok(())
+    }
+
+    /// Returns the parameters of the serialized config along with their
+    /// values, in serialization order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a ConfigParam, &ConfigValue)> {
+        self.schema.serialized_params().zip(&self.values)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        let index = self.schema.index_of(name).ok()?;
+        self.schema
+            .serialization_order
+            .iter()
+            .position(|&i| i == index)
+    }
+}
@@ -1,6 +1,38 @@
 use googletest::prelude::*;
 
-use vesc::{self, Command, EncodeError, ValuesMask};
+use vesc::{
+    self, ApplyAllFocParams, BatteryCutParams, CanBaud, CanMode, ChuckData, Command, EncodeError,
+    FileWriteParams, FluxLinkageOpenloopParams, FluxLinkageParams, GnssMask, HwType, ImuMask,
+    LogField, LogStartParams, SamplingMode, SetupValuesMask, StatsMask, ValuesMask,
+};
+
+#[test]
+fn encode_jump_to_bootloader() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::JumpToBootloader, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 1, 16, 33, 3]));
+}
+
+#[test]
+fn encode_erase_new_app() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::EraseNewApp(1030), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 2, 0, 0, 4, 6, 232, 129, 3]));
+}
+
+#[test]
+fn encode_write_new_app_data() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::WriteNewAppData(6, &[1, 2, 3, 4]);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 9, 3, 0, 0, 0, 6, 1, 2, 3, 4, 237, 194, 3])
+    );
+}
 
 #[test]
 fn encode_get_values() {
@@ -70,6 +102,103 @@ fn encode_set_handbrake() {
     assert_that!(buf[..size], eq([2, 5, 10, 255, 255, 235, 176, 169, 253, 3]));
 }
 
+#[test]
+fn encode_sample_print() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::SamplePrint(SamplingMode::Start, 1000, 4);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 19, 2, 3, 232, 4, 135, 47, 3]));
+}
+
+#[test]
+fn encode_detect_motor_param() {
+    let mut buf = [0u8; 32];
+
+    let size = vesc::encode(Command::DetectMotorParam(5.0, 2000.0, 0.05), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 13, 24, 0, 0, 19, 136, 0, 30, 132, 128, 0, 0, 0, 50, 77, 73, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_detect_motor_r_l() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::DetectMotorRL, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 25, 131, 24, 3]));
+}
+
+#[test]
+fn encode_detect_motor_flux_linkage() {
+    let mut buf = [0u8; 32];
+
+    let params = FluxLinkageParams {
+        current: 10.0,
+        min_rpm: 2000.0,
+        duty: 0.3,
+        resistance: 0.05,
+    };
+    let size = vesc::encode(Command::DetectMotorFluxLinkage(params), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 17, 26, 0, 0, 39, 16, 0, 30, 132, 128, 0, 0, 1, 44, 0, 0, 195, 80, 108, 145, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_detect_encoder() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::DetectEncoder(10.0), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 27, 0, 0, 39, 16, 101, 229, 3]));
+}
+
+#[test]
+fn encode_detect_hall_foc() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::DetectHallFoc(10.0), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 28, 0, 0, 39, 16, 2, 49, 3]));
+}
+
+#[test]
+fn encode_reboot() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::Reboot, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 29, 195, 156, 3]));
+}
+
+#[test]
+fn encode_get_decoded_ppm() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetDecodedPpm, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 31, 227, 222, 3]));
+}
+
+#[test]
+fn encode_get_decoded_adc() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetDecodedAdc, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 32, 36, 98, 3]));
+}
+
+#[test]
+fn encode_get_decoded_chuk() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetDecodedChuk, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 33, 52, 67, 3]));
+}
+
 #[test]
 fn encode_forward_can() {
     let mut buf = [0u8; 16];
@@ -81,6 +210,58 @@ fn encode_forward_can() {
     let command = Command::ForwardCan(7, &Command::SetCurrent(57.123));
     let size = vesc::encode(command, &mut buf).unwrap();
     assert_that!(buf[..size], eq([2, 7, 34, 7, 6, 0, 0, 223, 35, 26, 201, 3]));
+
+    let command = Command::ForwardCan(255, &Command::Reboot);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 3, 34, 255, 29, 40, 197, 3]));
+
+    let command = Command::ForwardCan(255, &Command::AppDisableOutput(true, 0));
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 8, 34, 255, 63, 1, 0, 0, 0, 0, 17, 97, 3])
+    );
+}
+
+#[test]
+fn encode_set_chuck_data() {
+    let mut buf = [0u8; 32];
+
+    let data = ChuckData {
+        js_x: 128,
+        js_y: 255,
+        bt_c: false,
+        bt_z: true,
+        acc_x: -200,
+        acc_y: 512,
+        acc_z: 0,
+        rev_has_state: true,
+        is_rev: true,
+    };
+    let size = vesc::encode(Command::SetChuckData(data), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 13, 35, 128, 255, 0, 1, 255, 56, 2, 0, 0, 0, 1, 1, 236, 123, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_custom_app_data() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::CustomAppData(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 36, 222, 173, 190, 239, 69, 229, 3]));
+}
+
+#[test]
+fn encode_get_values_setup() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetValuesSetup, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 47, 213, 141, 3]));
 }
 
 #[test]
@@ -104,6 +285,629 @@ fn encode_get_values_selective() {
     assert_that!(buf[..size], eq([2, 5, 50, 0, 2, 8, 128, 62, 44, 3]));
 }
 
+#[test]
+fn encode_get_values_setup_selective() {
+    let mut buf = [0u8; 16];
+
+    let mask = SetupValuesMask::SPEED | SetupValuesMask::BATTERY_LEVEL;
+    let size = vesc::encode(Command::GetValuesSetupSelective(mask), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 51, 0, 0, 1, 64, 153, 201, 3]));
+}
+
+#[test]
+fn encode_detect_motor_flux_linkage_openloop() {
+    let mut buf = [0u8; 32];
+
+    let params = FluxLinkageOpenloopParams {
+        current: 10.0,
+        erpm_per_sec: 2000.0,
+        duty: 0.3,
+        resistance: 0.05,
+        inductance: 0.00001,
+    };
+    let size = vesc::encode(Command::DetectMotorFluxLinkageOpenloop(params), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 21, 57, 0, 0, 39, 16, 0, 30, 132, 128, 0, 0, 1, 44, 0, 0, 195, 80, 0, 0, 3, 232, 19,
+            28, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_detect_apply_all_foc() {
+    let mut buf = [0u8; 32];
+
+    let params = ApplyAllFocParams {
+        detect_can: true,
+        max_power_loss: 50.0,
+        min_current_in: -20.0,
+        max_current_in: 60.0,
+        openloop_rpm: 1500.0,
+        sl_erpm: 0.0,
+    };
+    let size = vesc::encode(Command::DetectApplyAllFoc(params), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 22, 58, 1, 0, 0, 195, 80, 255, 255, 177, 224, 0, 0, 234, 96, 0, 22, 227, 96, 0, 0,
+            0, 0, 43, 81, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_jump_to_bootloader_all_can() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::JumpToBootloaderAllCan, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 59, 135, 56, 3]));
+}
+
+#[test]
+fn encode_erase_new_app_all_can() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::EraseNewAppAllCan(1030), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 60, 0, 0, 4, 6, 43, 199, 3]));
+}
+
+#[test]
+fn encode_write_new_app_data_all_can() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::WriteNewAppDataAllCan(6, &[1, 2, 3, 4]);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 9, 61, 0, 0, 0, 6, 1, 2, 3, 4, 106, 183, 3])
+    );
+}
+
+#[test]
+fn encode_app_disable_output() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::AppDisableOutput(false, 1000), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 6, 63, 0, 0, 0, 3, 232, 195, 250, 3]));
+
+    let size = vesc::encode(Command::AppDisableOutput(true, -1), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 6, 63, 1, 255, 255, 255, 255, 217, 17, 3])
+    );
+}
+
+#[test]
+fn encode_get_imu_data() {
+    let mut buf = [0u8; 16];
+
+    let mask = ImuMask::ROLL | ImuMask::PITCH | ImuMask::YAW;
+    let size = vesc::encode(Command::GetImuData(mask), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 3, 65, 0, 7, 90, 122, 3]));
+
+    let mask = ImuMask::Q0 | ImuMask::Q1 | ImuMask::Q2 | ImuMask::Q3;
+    let size = vesc::encode(Command::GetImuData(mask), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 3, 65, 240, 0, 57, 92, 3]));
+}
+
+#[test]
+fn encode_erase_bootloader() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::EraseBootloader, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 73, 217, 237, 3]));
+
+    let size = vesc::encode(Command::EraseBootloaderAllCan, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 74, 233, 142, 3]));
+}
+
+#[test]
+fn encode_get_decoded_balance() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetDecodedBalance, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 79, 185, 43, 3]));
+}
+
+#[test]
+fn encode_write_new_app_data_lzo() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::WriteNewAppDataLzo(6, 100, &[1, 2, 3]);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 10, 81, 0, 0, 0, 6, 0, 100, 1, 2, 3, 246, 58, 3])
+    );
+}
+
+#[test]
+fn encode_write_new_app_data_all_can_lzo() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::WriteNewAppDataAllCanLzo(6, 100, &[1, 2, 3]);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 10, 82, 0, 0, 0, 6, 0, 100, 1, 2, 3, 71, 245, 3])
+    );
+}
+
+#[test]
+fn encode_can_fwd_frame() {
+    let mut buf = [0u8; 32];
+
+    let size = vesc::encode(Command::CanFwdFrame(0x123, false, &[1, 2, 3]), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 9, 85, 0, 0, 1, 35, 0, 1, 2, 3, 185, 28, 3])
+    );
+
+    let data = [0, 1, 2, 3, 4, 5, 6, 7];
+    let size = vesc::encode(Command::CanFwdFrame(0x18FF50E5, true, &data), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 14, 85, 24, 255, 80, 229, 1, 0, 1, 2, 3, 4, 5, 6, 7, 250, 187, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_can_fwd_frame_invalid() {
+    let mut buf = [0u8; 32];
+
+    let result = vesc::encode(Command::CanFwdFrame(0x800, false, &[]), &mut buf);
+    assert_that!(result, err(eq(&EncodeError::InvalidCanFrame)));
+
+    let result = vesc::encode(Command::CanFwdFrame(0x2000_0000, true, &[]), &mut buf);
+    assert_that!(result, err(eq(&EncodeError::InvalidCanFrame)));
+
+    let result = vesc::encode(Command::CanFwdFrame(1, true, &[0; 9]), &mut buf);
+    assert_that!(result, err(eq(&EncodeError::InvalidCanFrame)));
+}
+
+#[test]
+fn encode_set_battery_cut() {
+    let mut buf = [0u8; 32];
+
+    let params = BatteryCutParams {
+        start: 42.0,
+        end: 39.5,
+        store: true,
+        forward_can: false,
+    };
+    let size = vesc::encode(Command::SetBatteryCut(params), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 11, 86, 0, 0, 164, 16, 0, 0, 154, 76, 1, 0, 171, 42, 3])
+    );
+}
+
+#[test]
+fn encode_set_ble_name() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::SetBleName("Skate"), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 7, 87, 83, 107, 97, 116, 101, 0, 103, 245, 3])
+    );
+}
+
+#[test]
+fn encode_set_ble_pin() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::SetBlePin("123456"), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 8, 88, 49, 50, 51, 52, 53, 54, 0, 21, 220, 3])
+    );
+}
+
+#[test]
+fn encode_set_can_mode() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::SetCanMode(CanMode::CommBridge, true, false);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 4, 89, 1, 0, 2, 145, 62, 3]));
+}
+
+#[test]
+fn encode_get_imu_calibration() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetImuCalibration(12.5), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 90, 0, 0, 48, 212, 221, 240, 3]));
+}
+
+#[test]
+fn encode_get_custom_config_xml() {
+    let mut buf = [0u8; 32];
+
+    let size = vesc::encode(Command::GetCustomConfigXml(1, 768, 384), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 10, 92, 1, 0, 0, 1, 128, 0, 0, 3, 0, 216, 242, 3])
+    );
+}
+
+#[test]
+fn encode_get_custom_config() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetCustomConfig(1), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 93, 1, 104, 194, 3]));
+}
+
+#[test]
+fn encode_get_custom_config_default() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetCustomConfigDefault(1), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 94, 1, 61, 145, 3]));
+}
+
+#[test]
+fn encode_set_custom_config() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::SetCustomConfig(1, &[10, 20, 30]), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 95, 1, 10, 20, 30, 253, 246, 3]));
+}
+
+#[test]
+fn encode_bms_get_values() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::BmsGetValues, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 96, 108, 166, 3]));
+}
+
+#[test]
+fn encode_bms_set_charge_allowed() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::BmsSetChargeAllowed(true), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 97, 1, 40, 58, 3]));
+}
+
+#[test]
+fn encode_bms_set_balance_override() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::BmsSetBalanceOverride(2, 1), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 3, 98, 2, 1, 131, 72, 3]));
+}
+
+#[test]
+fn encode_bms_reset_counters() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::BmsResetCounters(true, false), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 3, 99, 1, 0, 241, 10, 3]));
+}
+
+#[test]
+fn encode_bms_force_balance() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::BmsForceBalance(true), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 100, 1, 215, 207, 3]));
+}
+
+#[test]
+fn encode_bms_zero_current_offset() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::BmsZeroCurrentOffset, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 101, 60, 3, 3]));
+}
+
+#[test]
+fn encode_jump_to_bootloader_hw() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::JumpToBootloaderHw(HwType::CustomModule);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 102, 2, 129, 206, 3]));
+
+    let command = Command::JumpToBootloaderAllCanHw(HwType::CustomModule);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 106, 2, 196, 163, 3]));
+}
+
+#[test]
+fn encode_erase_new_app_hw() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::EraseNewAppHw(HwType::VescBms, 1006), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 6, 103, 1, 0, 0, 3, 238, 116, 187, 3]));
+
+    let command = Command::EraseNewAppAllCanHw(HwType::VescBms, 1006);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 6, 107, 1, 0, 0, 3, 238, 127, 88, 3]));
+}
+
+#[test]
+fn encode_write_new_app_data_hw() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::WriteNewAppDataHw(HwType::Vesc, 6, &[1, 2, 3]);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 9, 104, 0, 0, 0, 0, 6, 1, 2, 3, 151, 114, 3])
+    );
+
+    let command = Command::WriteNewAppDataAllCanHw(HwType::Vesc, 6, &[1, 2, 3]);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 9, 108, 0, 0, 0, 0, 6, 1, 2, 3, 11, 157, 3])
+    );
+}
+
+#[test]
+fn encode_erase_bootloader_hw() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::EraseBootloaderHw(HwType::VescBms), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 105, 1, 161, 147, 3]));
+
+    let command = Command::EraseBootloaderAllCanHw(HwType::CustomModule);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 109, 2, 93, 52, 3]));
+}
+
+#[test]
+fn encode_set_odometer() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::SetOdometer(1234567), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 110, 0, 18, 214, 135, 165, 153, 3]));
+}
+
+#[test]
+fn encode_get_stats() {
+    let mut buf = [0u8; 16];
+
+    let mask = StatsMask::SPEED_AVG | StatsMask::SPEED_MAX | StatsMask::COUNT_TIME;
+    let size = vesc::encode(Command::GetStats(mask), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 3, 128, 4, 3, 199, 253, 3]));
+}
+
+#[test]
+fn encode_reset_stats() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::ResetStats(true), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 129, 1, 56, 136, 3]));
+}
+
+#[test]
+fn encode_lisp_read_code() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::LispReadCode(512, 100), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 9, 130, 0, 0, 0, 100, 0, 0, 2, 0, 143, 109, 3])
+    );
+}
+
+#[test]
+fn encode_lisp_write_code() {
+    let mut buf = [0u8; 32];
+
+    let size = vesc::encode(Command::LispWriteCode(512, b"(+ 1 2)"), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 12, 131, 0, 0, 2, 0, 40, 43, 32, 49, 32, 50, 41, 96, 59, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_lisp_erase_code() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::LispEraseCode(-1), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 132, 255, 255, 255, 255, 50, 25, 3]));
+}
+
+#[test]
+fn encode_lisp_set_running() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::LispSetRunning(true), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 133, 1, 244, 76, 3]));
+}
+
+#[test]
+fn encode_lisp_get_stats() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::LispGetStats(false), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 2, 134, 0, 177, 62, 3]));
+}
+
+#[test]
+fn encode_lisp_repl_cmd() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::LispReplCmd("(+ 1 2)"), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 9, 138, 40, 43, 32, 49, 32, 50, 41, 0, 163, 3, 3])
+    );
+}
+
+#[test]
+fn encode_file_list() {
+    let mut buf = [0u8; 32];
+
+    let size = vesc::encode(Command::FileList("/log", "a.csv"), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 12, 140, 47, 108, 111, 103, 0, 97, 46, 99, 115, 118, 0, 39, 33, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_file_read() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::FileRead("/a", 384), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 8, 141, 47, 97, 0, 0, 0, 1, 128, 247, 251, 3])
+    );
+}
+
+#[test]
+fn encode_file_write() {
+    let mut buf = [0u8; 32];
+    let params = FileWriteParams {
+        path: "/a",
+        offset: 4,
+        size: 6,
+        data: &[5, 6],
+    };
+
+    let size = vesc::encode(Command::FileWrite(params), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 14, 142, 47, 97, 0, 0, 0, 0, 4, 0, 0, 0, 6, 5, 6, 7, 136, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_file_mkdir() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::FileMkdir("/d"), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 4, 143, 47, 100, 0, 100, 207, 3]));
+}
+
+#[test]
+fn encode_file_remove() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::FileRemove("/d"), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 4, 144, 47, 100, 0, 171, 134, 3]));
+}
+
+#[test]
+fn encode_log_start() {
+    let mut buf = [0u8; 16];
+    let params = LogStartParams {
+        field_count: 3,
+        rate_hz: 10.0,
+        append_time: true,
+        append_gnss: false,
+        append_gnss_time: true,
+    };
+
+    let size = vesc::encode(Command::LogStart(params), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([2, 10, 145, 0, 3, 65, 32, 0, 0, 1, 0, 1, 160, 6, 3])
+    );
+}
+
+#[test]
+fn encode_log_stop() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::LogStop, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 146, 163, 251, 3]));
+}
+
+#[test]
+fn encode_log_config_field() {
+    let mut buf = [0u8; 32];
+    let field = LogField {
+        index: 2,
+        key: "v",
+        name: "Volt",
+        unit: "V",
+        precision: 2,
+        is_relative: false,
+        is_timestamp: true,
+    };
+
+    let size = vesc::encode(Command::LogConfigField(field), &mut buf).unwrap();
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 15, 147, 0, 2, 118, 0, 86, 111, 108, 116, 0, 86, 0, 2, 0, 1, 118, 233, 3
+        ])
+    );
+}
+
+#[test]
+fn encode_get_gnss() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::GetGnss(GnssMask::all()), &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 3, 150, 3, 255, 129, 58, 3]));
+}
+
+#[test]
+fn encode_shutdown() {
+    let mut buf = [0u8; 16];
+
+    let size = vesc::encode(Command::Shutdown, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 1, 156, 66, 53, 3]));
+}
+
+#[test]
+fn encode_can_update_baud_all() {
+    let mut buf = [0u8; 16];
+
+    let command = Command::CanUpdateBaudAll(CanBaud::Kbits250, 1000);
+    let size = vesc::encode(command, &mut buf).unwrap();
+    assert_that!(buf[..size], eq([2, 5, 158, 0, 250, 3, 232, 228, 196, 3]));
+}
+
+#[test]
+fn encode_long_frame() {
+    let mut buf = [0u8; 512];
+
+    let size = vesc::encode(Command::WriteNewAppData(0, &[0xAA; 300]), &mut buf).unwrap();
+    assert_that!(size, eq(311));
+    assert_that!(buf[..8], eq([3, 1, 49, 3, 0, 0, 0, 0]));
+    assert_that!(buf[8..size - 3], each(eq(&0xAA)));
+    assert_that!(buf[size - 3..size], eq([132, 150, 3]));
+}
+
+#[test]
+fn encode_long_frame_buffer_too_small() {
+    for n in [0, 100, 306, 308, 310] {
+        let mut buf = vec![0u8; n];
+        let result = vesc::encode(Command::WriteNewAppData(0, &[0xAA; 300]), &mut buf);
+        assert_that!(result, err(eq(&EncodeError::BufferTooSmall)));
+    }
+}
+
+#[test]
+fn encode_payload_too_large() {
+    let mut buf = vec![0u8; 70000];
+
+    let result = vesc::encode(Command::WriteNewAppData(0, &[0xAA; 65531]), &mut buf);
+    assert_that!(result, err(eq(&EncodeError::PayloadTooLarge)));
+}
+
 #[test]
 fn encode_buf_perfect_fit() {
     let mut buf = [0u8; 10];
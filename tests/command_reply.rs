@@ -1,6 +1,279 @@
 use googletest::prelude::*;
 
-use vesc::{CommandReply, DecodeError, FaultCode, Values};
+use vesc::{
+    BldcDetection, BmsValues, CommandReply, ConfigXmlChunk, DecodeError, DecodedAdc,
+    DecodedBalance, DecodedPpm, EXPERIMENT_MAX_SAMPLES, EncoderDetection, FaultCode, FileEntry,
+    FluxLinkageDetection, GnssData, HallDetection, ImuCalibration, ImuData, LispStats, MotorSample,
+    ResistanceInductance, SetupValues, StatsValues, Values,
+};
+
+#[test]
+fn decode_erase_new_app() {
+    let input = [2, 2, 2, 1, 118, 67, 3];
+    let expected = (eq(&7), pat!(&CommandReply::EraseNewApp(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+
+    let input = [2, 2, 2, 0, 102, 98, 3];
+    let expected = (eq(&7), pat!(&CommandReply::EraseNewApp(eq(false))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_write_new_app_data() {
+    let input = [2, 6, 3, 1, 0, 0, 1, 134, 166, 206, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::WriteNewAppData(eq(true), eq(390))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+
+    let input = [2, 6, 3, 0, 0, 0, 1, 134, 12, 159, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::WriteNewAppData(eq(false), eq(390))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_sample_print() {
+    let input = [
+        2, 35, 19, 63, 192, 0, 0, 192, 0, 0, 0, 65, 64, 0, 0, 62, 128, 0, 0, 65, 192, 0, 0, 65, 66,
+        0, 0, 64, 96, 0, 0, 70, 234, 96, 0, 5, 2, 31, 253, 3,
+    ];
+
+    let expected = (
+        eq(&40),
+        pat!(&CommandReply::SamplePrint(pat!(MotorSample {
+            current1: eq(1.5),
+            current2: eq(-2.0),
+            voltage1: eq(12.0),
+            voltage2: eq(0.25),
+            voltage3: eq(24.0),
+            voltage_zero: eq(12.125),
+            current_total: eq(3.5),
+            switching_frequency: eq(30000.0),
+            status: eq(5),
+            phase: eq(2),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_rotor_position() {
+    let input = [2, 5, 22, 0, 188, 97, 78, 211, 80, 3];
+    let expected = (
+        eq(&10),
+        pat!(&CommandReply::RotorPosition(approx_eq(123.45678))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_experiment_sample() {
+    let input = [2, 9, 23, 0, 0, 58, 152, 255, 255, 246, 60, 165, 4, 3];
+
+    let Ok((size, CommandReply::ExperimentSample(samples))) = vesc::decode(&input) else {
+        panic!("expected experiment samples");
+    };
+    assert_that!(size, eq(14));
+    assert_that!(samples.samples(), elements_are![eq(&1.5), eq(&-0.25)]);
+}
+
+#[test]
+fn decode_experiment_sample_max_samples() {
+    let mut input = vec![2, 253, 23];
+    for i in 0..63 {
+        input.extend((i * 10000i32).to_be_bytes());
+    }
+    input.extend([255, 29, 3]);
+
+    let Ok((size, CommandReply::ExperimentSample(samples))) = vesc::decode(&input) else {
+        panic!("expected experiment samples");
+    };
+    assert_that!(size, eq(258));
+    assert_that!(samples.samples().len(), eq(EXPERIMENT_MAX_SAMPLES));
+    assert_that!(samples.samples()[62], eq(62.0));
+}
+
+#[test]
+fn decode_experiment_sample_too_many_samples() {
+    let mut input = [0; 263];
+    input[..4].copy_from_slice(&[3, 1, 1, 23]);
+    input[260..].copy_from_slice(&[122, 251, 3]);
+    assert_that!(
+        vesc::decode(&input),
+        err(eq(&DecodeError::CapacityExceeded))
+    );
+}
+
+#[test]
+fn decode_detect_motor_param() {
+    let input = [
+        2, 18, 24, 0, 0, 176, 67, 255, 255, 251, 30, 255, 1, 3, 2, 5, 6, 4, 255, 0, 12, 255, 3,
+    ];
+
+    let expected = (
+        eq(&23),
+        pat!(&CommandReply::DetectMotorParam(pat!(BldcDetection {
+            cycle_int_limit: approx_eq(45.123),
+            coupling_k: approx_eq(-1.25),
+            hall_table: eq([255, 1, 3, 2, 5, 6, 4, 255]),
+            hall_res: eq(0),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_detect_motor_r_l() {
+    let input = [
+        2, 13, 25, 0, 0, 200, 34, 0, 0, 95, 247, 0, 0, 12, 138, 125, 201, 3,
+    ];
+
+    let expected = (
+        eq(&18),
+        pat!(&CommandReply::DetectMotorRL(pat!(ResistanceInductance {
+            resistance: approx_eq(0.051234),
+            inductance: approx_eq(24.567),
+            ld_lq_diff: approx_eq(3.21),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_detect_motor_r_l_without_ld_lq_diff() {
+    let input = [2, 9, 25, 0, 0, 200, 34, 0, 0, 95, 247, 239, 30, 3];
+
+    let expected = (
+        eq(&14),
+        pat!(&CommandReply::DetectMotorRL(pat!(ResistanceInductance {
+            resistance: approx_eq(0.051234),
+            inductance: approx_eq(24.567),
+            ld_lq_diff: approx_eq(0.0),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+
+    for i in 1..input.len() {
+        let expected = &DecodeError::IncompleteData;
+        assert_that!(vesc::decode(&input[..i]), err(eq(expected)));
+    }
+}
+
+#[test]
+fn decode_detect_motor_flux_linkage() {
+    let input = [2, 5, 26, 0, 0, 204, 121, 254, 115, 3];
+    let expected = (
+        eq(&10),
+        pat!(&CommandReply::DetectMotorFluxLinkage(approx_eq(0.0052345))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_detect_encoder() {
+    let input = [2, 10, 27, 7, 91, 205, 21, 0, 106, 207, 192, 1, 59, 34, 3];
+
+    let expected = (
+        eq(&15),
+        pat!(&CommandReply::DetectEncoder(pat!(EncoderDetection {
+            offset: approx_eq(123.45679),
+            ratio: approx_eq(7.0),
+            inverted: eq(true),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_detect_hall_foc() {
+    let input = [2, 10, 28, 255, 79, 152, 113, 25, 46, 9, 255, 0, 123, 136, 3];
+
+    let expected = (
+        eq(&15),
+        pat!(&CommandReply::DetectHallFoc(pat!(HallDetection {
+            hall_table: eq([255, 79, 152, 113, 25, 46, 9, 255]),
+            success: eq(true),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_decoded_ppm() {
+    let input = [2, 9, 31, 255, 248, 94, 224, 0, 22, 227, 96, 240, 111, 3];
+    let expected = (
+        eq(&14),
+        pat!(&CommandReply::GetDecodedPpm(pat!(DecodedPpm {
+            value: approx_eq(-0.5),
+            pulse_len: approx_eq(1.5),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_decoded_adc() {
+    let input = [
+        2, 17, 32, 0, 3, 208, 144, 0, 25, 45, 80, 0, 0, 0, 0, 0, 4, 147, 224, 56, 184, 3,
+    ];
+    let expected = (
+        eq(&22),
+        pat!(&CommandReply::GetDecodedAdc(pat!(DecodedAdc {
+            value: approx_eq(0.25),
+            voltage: approx_eq(1.65),
+            value2: approx_eq(0.0),
+            voltage2: approx_eq(0.3),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_decoded_chuk() {
+    let input = [2, 5, 33, 255, 244, 142, 80, 180, 182, 3];
+    let expected = (
+        eq(&10),
+        pat!(&CommandReply::GetDecodedChuk(approx_eq(-0.75))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_custom_app_data() {
+    let input = [2, 5, 36, 222, 173, 190, 239, 69, 229, 3];
+
+    let Ok((size, CommandReply::CustomAppData(data))) = vesc::decode(&input) else {
+        panic!("expected a custom app data reply");
+    };
+    assert_that!(size, eq(10));
+    assert_that!(data.as_slice(), eq(&[0xDE, 0xAD, 0xBE, 0xEF]));
+}
+
+#[test]
+fn decode_custom_app_data_empty() {
+    let input = [2, 1, 36, 100, 230, 3];
+
+    let Ok((size, CommandReply::CustomAppData(data))) = vesc::decode(&input) else {
+        panic!("expected a custom app data reply");
+    };
+    assert_that!(size, eq(6));
+    assert_that!(data.as_slice(), is_empty());
+}
+
+#[test]
+fn decode_custom_app_data_too_large() {
+    let mut input = [0xAA; 526];
+    input[..4].copy_from_slice(&[3, 2, 8, 36]);
+    input[523..].copy_from_slice(&[20, 106, 3]);
+    assert_that!(
+        vesc::decode(&input),
+        err(eq(&DecodeError::CapacityExceeded))
+    );
+}
 
 #[test]
 fn decode_get_values_zero_rpm() {
@@ -165,6 +438,66 @@ fn decode_get_values_motor_fault() {
     assert_that!(vesc::decode(&input), ok(expected));
 }
 
+#[test]
+fn decode_get_values_setup() {
+    let input = [
+        2, 70, 47, 1, 19, 1, 56, 0, 0, 4, 210, 255, 255, 255, 6, 0, 153, 0, 0, 20, 192, 0, 0, 32,
+        153, 1, 225, 2, 222, 0, 0, 48, 57, 0, 0, 0, 230, 0, 6, 248, 85, 0, 0, 35, 52, 0, 0, 59,
+        130, 0, 0, 69, 156, 4, 106, 124, 36, 0, 1, 3, 0, 7, 209, 44, 0, 1, 226, 64, 0, 54, 240,
+        116, 50, 176, 3,
+    ];
+
+    let expected = (
+        eq(&75),
+        pat!(&CommandReply::GetValuesSetup(pat!(SetupValues {
+            temp_mosfet: approx_eq(27.5),
+            temp_motor: approx_eq(31.2),
+            current_motor: approx_eq(12.34),
+            current_input: approx_eq(-2.5),
+            duty_cycle: approx_eq(0.153),
+            rpm: approx_eq(5312.0),
+            speed: approx_eq(8.345),
+            voltage_in: approx_eq(48.1),
+            battery_level: approx_eq(0.734),
+            amp_hours: approx_eq(1.2345),
+            amp_hours_charged: approx_eq(0.023),
+            watt_hours: approx_eq(45.6789),
+            watt_hours_charged: approx_eq(0.9012),
+            distance: approx_eq(15.234),
+            distance_abs: approx_eq(17.82),
+            pid_pos: approx_eq(74.08746),
+            fault_code: eq(FaultCode::None),
+            controller_id: eq(1),
+            num_vescs: eq(3),
+            battery_watt_hours: approx_eq(512.3),
+            odometer: eq(123456),
+            uptime_ms: eq(3600500),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_values_setup_selective() {
+    let input = [
+        2, 16, 51, 0, 20, 1, 64, 0, 0, 32, 153, 2, 222, 2, 0, 1, 226, 64, 25, 177, 3,
+    ];
+
+    let expected = (
+        eq(&21),
+        pat!(&CommandReply::GetValuesSetupSelective(pat!(SetupValues {
+            current_motor: approx_eq(0.0),
+            speed: approx_eq(8.345),
+            battery_level: approx_eq(0.734),
+            num_vescs: eq(2),
+            odometer: eq(123456),
+            uptime_ms: eq(0),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
 #[test]
 fn decode_get_values_selective_zero_rpm() {
     let input = [
@@ -253,6 +586,812 @@ fn decode_get_values_selective_fault_code() {
     assert_that!(vesc::decode(&input), ok(expected));
 }
 
+#[test]
+fn decode_detect_motor_flux_linkage_openloop() {
+    let input = [2, 5, 57, 0, 0, 204, 121, 24, 21, 3];
+    let expected = (
+        eq(&10),
+        pat!(&CommandReply::DetectMotorFluxLinkageOpenloop(pat!(
+            FluxLinkageDetection {
+                flux_linkage: approx_eq(0.0052345),
+                encoder_offset: approx_eq(0.0),
+                encoder_ratio: approx_eq(0.0),
+                encoder_inverted: eq(false),
+            }
+        ))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_detect_motor_flux_linkage_openloop_with_encoder() {
+    let input = [
+        2, 14, 57, 0, 0, 204, 121, 7, 91, 202, 0, 0, 106, 207, 192, 1, 27, 250, 3,
+    ];
+    let expected = (
+        eq(&19),
+        pat!(&CommandReply::DetectMotorFluxLinkageOpenloop(pat!(
+            FluxLinkageDetection {
+                flux_linkage: approx_eq(0.0052345),
+                encoder_offset: approx_eq(123.456),
+                encoder_ratio: approx_eq(7.0),
+                encoder_inverted: eq(true),
+            }
+        ))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_detect_apply_all_foc() {
+    let input = [2, 3, 58, 0, 1, 18, 69, 3];
+    let expected = (eq(&8), pat!(&CommandReply::DetectApplyAllFoc(eq(1))));
+    assert_that!(vesc::decode(&input), ok(expected));
+
+    let input = [2, 3, 58, 255, 246, 142, 66, 3];
+    let expected = (eq(&8), pat!(&CommandReply::DetectApplyAllFoc(eq(-10))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_erase_new_app_all_can() {
+    let input = [2, 2, 60, 1, 80, 217, 3];
+    let expected = (eq(&7), pat!(&CommandReply::EraseNewAppAllCan(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_write_new_app_data_all_can() {
+    let input = [2, 6, 61, 1, 0, 0, 0, 6, 171, 88, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::WriteNewAppDataAllCan(eq(true), eq(6))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_imu_data() {
+    let input = [
+        2, 19, 65, 16, 56, 63, 0, 0, 0, 190, 128, 0, 0, 65, 28, 245, 195, 63, 128, 0, 0, 38, 119, 3,
+    ];
+
+    let expected = (
+        eq(&24),
+        pat!(&CommandReply::GetImuData(pat!(ImuData {
+            roll: eq(0.0),
+            acc_x: eq(0.5),
+            acc_y: eq(-0.25),
+            acc_z: approx_eq(9.81),
+            gyro_x: eq(0.0),
+            q0: eq(1.0),
+            q1: eq(0.0),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_erase_bootloader() {
+    let input = [2, 2, 73, 1, 167, 117, 3];
+    let expected = (eq(&7), pat!(&CommandReply::EraseBootloader(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_erase_bootloader_all_can() {
+    let input = [2, 2, 74, 0, 226, 7, 3];
+    let expected = (
+        eq(&7),
+        pat!(&CommandReply::EraseBootloaderAllCan(eq(false))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_plot_init() {
+    let input = [
+        2, 14, 75, 84, 105, 109, 101, 0, 67, 117, 114, 114, 101, 110, 116, 0, 22, 17, 3,
+    ];
+
+    let Ok((size, CommandReply::PlotInit(names))) = vesc::decode(&input) else {
+        panic!("expected a plot init message");
+    };
+    assert_that!(size, eq(19));
+    assert_that!(names.x(), eq("Time"));
+    assert_that!(names.y(), eq("Current"));
+}
+
+#[test]
+fn decode_plot_init_missing_name() {
+    let input = [2, 6, 75, 84, 105, 109, 101, 0, 178, 198, 3];
+    assert_that!(vesc::decode(&input), err(eq(&DecodeError::InvalidFrame)));
+}
+
+#[test]
+fn decode_plot_data() {
+    let input = [2, 9, 76, 63, 0, 0, 0, 192, 64, 0, 0, 219, 222, 3];
+    let expected = (eq(&14), pat!(&CommandReply::PlotData(eq(0.5), eq(-3.0))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_plot_add_graph() {
+    let input = [2, 7, 77, 77, 111, 116, 111, 114, 0, 62, 117, 3];
+
+    let Ok((size, CommandReply::PlotAddGraph(name))) = vesc::decode(&input) else {
+        panic!("expected a plot add graph message");
+    };
+    assert_that!(size, eq(12));
+    assert_that!(name.as_str(), ok(eq("Motor")));
+}
+
+#[test]
+fn decode_plot_set_graph() {
+    let input = [2, 2, 78, 1, 62, 226, 3];
+    let expected = (eq(&7), pat!(&CommandReply::PlotSetGraph(eq(1))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_decoded_balance() {
+    let input = [
+        2, 41, 79, 0, 190, 188, 32, 255, 206, 104, 176, 0, 7, 161, 32, 0, 0, 3, 232, 0, 133, 131,
+        176, 0, 2, 0, 3, 0, 44, 64, 32, 0, 47, 77, 96, 0, 1, 232, 72, 255, 240, 189, 192, 127, 146,
+        3,
+    ];
+    let expected = (
+        eq(&46),
+        pat!(&CommandReply::GetDecodedBalance(pat!(DecodedBalance {
+            pid_output: approx_eq(12.5),
+            pitch: approx_eq(-3.25),
+            roll: approx_eq(0.5),
+            loop_time_us: eq(1000),
+            motor_current: approx_eq(8.75),
+            state: eq(2),
+            switch_state: eq(3),
+            adc1: approx_eq(2.9),
+            adc2: approx_eq(3.1),
+            debug1: approx_eq(0.125),
+            debug2: approx_eq(-1.0),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_decoded_balance_without_debug() {
+    let input = [
+        2, 33, 79, 0, 190, 188, 32, 255, 206, 104, 176, 0, 7, 161, 32, 0, 0, 3, 232, 0, 133, 131,
+        176, 0, 2, 0, 3, 0, 44, 64, 32, 0, 47, 77, 96, 157, 29, 3,
+    ];
+    let expected = (
+        eq(&38),
+        pat!(&CommandReply::GetDecodedBalance(pat!(DecodedBalance {
+            pid_output: approx_eq(12.5),
+            adc2: approx_eq(3.1),
+            debug1: approx_eq(0.0),
+            debug2: approx_eq(0.0),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_write_new_app_data_lzo() {
+    let input = [2, 6, 81, 1, 0, 0, 0, 6, 255, 163, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::WriteNewAppDataLzo(eq(true), eq(6))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_write_new_app_data_all_can_lzo() {
+    let input = [2, 6, 82, 1, 0, 0, 0, 6, 49, 67, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::WriteNewAppDataAllCanLzo(eq(true), eq(6))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_can_fwd_frame() {
+    let input = [2, 8, 85, 24, 255, 80, 229, 1, 170, 187, 141, 27, 3];
+
+    let Ok((size, CommandReply::CanFwdFrame(frame))) = vesc::decode(&input) else {
+        panic!("expected a CAN frame");
+    };
+    assert_that!(size, eq(13));
+    assert_that!(frame.id, eq(0x18FF50E5));
+    assert_that!(frame.is_extended, eq(true));
+    assert_that!(frame.data(), eq(&[0xAA, 0xBB]));
+}
+
+#[test]
+fn decode_can_fwd_frame_too_long() {
+    let input = [
+        2, 15, 85, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 137, 132, 3,
+    ];
+    assert_that!(
+        vesc::decode(&input),
+        err(eq(&DecodeError::CapacityExceeded))
+    );
+}
+
+#[test]
+fn decode_set_battery_cut() {
+    let input = [2, 1, 86, 58, 51, 3];
+    let expected = (eq(&6), pat!(&CommandReply::SetBatteryCut));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_set_ble_name() {
+    let input = [2, 2, 87, 1, 135, 9, 3];
+    let expected = (eq(&7), pat!(&CommandReply::SetBleName(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_set_ble_pin() {
+    let input = [2, 2, 88, 0, 135, 22, 3];
+    let expected = (eq(&7), pat!(&CommandReply::SetBlePin(eq(false))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_set_can_mode() {
+    let input = [2, 1, 89, 203, 220, 3];
+    let expected = (eq(&6), pat!(&CommandReply::SetCanMode));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_imu_calibration() {
+    let input = [
+        2, 37, 90, 0, 19, 18, 208, 255, 202, 152, 32, 0, 190, 188, 32, 0, 0, 46, 224, 255, 255,
+        123, 48, 0, 14, 244, 32, 0, 7, 161, 32, 255, 236, 237, 48, 0, 1, 232, 72, 169, 103, 3,
+    ];
+
+    let expected = (
+        eq(&42),
+        pat!(&CommandReply::GetImuCalibration(pat!(ImuCalibration {
+            roll: approx_eq(1.25),
+            pitch: approx_eq(-3.5),
+            yaw: approx_eq(12.5),
+            acc_offset_x: approx_eq(0.012),
+            acc_offset_y: approx_eq(-0.034),
+            acc_offset_z: approx_eq(0.98),
+            gyro_offset_x: approx_eq(0.5),
+            gyro_offset_y: approx_eq(-1.25),
+            gyro_offset_z: approx_eq(0.125),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_custom_config_xml() {
+    let input = [
+        2, 14, 92, 1, 0, 0, 3, 232, 0, 0, 3, 0, 120, 156, 1, 2, 118, 63, 3,
+    ];
+
+    let Ok((size, CommandReply::GetCustomConfigXml(chunk))) = vesc::decode(&input) else {
+        panic!("expected a custom config XML reply");
+    };
+    assert_that!(size, eq(19));
+    assert_that!(
+        chunk,
+        pat!(ConfigXmlChunk {
+            index: eq(1),
+            total_len: eq(1000),
+            offset: eq(768),
+            ..
+        })
+    );
+    assert_that!(chunk.data.as_slice(), eq(&[0x78, 0x9C, 1, 2]));
+}
+
+#[test]
+fn decode_get_custom_config() {
+    let input = [2, 5, 93, 1, 10, 20, 30, 185, 117, 3];
+
+    let Ok((size, CommandReply::GetCustomConfig(index, values))) = vesc::decode(&input) else {
+        panic!("expected a custom config reply");
+    };
+    assert_that!(size, eq(10));
+    assert_that!(index, eq(1));
+    assert_that!(values.as_slice(), eq(&[10, 20, 30]));
+}
+
+#[test]
+fn decode_get_custom_config_default() {
+    let input = [2, 4, 94, 1, 40, 50, 121, 15, 3];
+
+    let Ok((size, CommandReply::GetCustomConfigDefault(index, values))) = vesc::decode(&input)
+    else {
+        panic!("expected a custom config reply");
+    };
+    assert_that!(size, eq(9));
+    assert_that!(index, eq(1));
+    assert_that!(values.as_slice(), eq(&[40, 50]));
+}
+
+#[test]
+fn decode_set_custom_config() {
+    let input = [2, 1, 95, 171, 26, 3];
+    let expected = (eq(&6), pat!(&CommandReply::SetCustomConfig));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_bms_get_values() {
+    let input = [
+        2, 71, 96, 3, 1, 11, 0, 0, 7, 161, 32, 255, 65, 67, 224, 255, 69, 20, 112, 0, 0, 5, 220, 0,
+        1, 37, 242, 3, 16, 104, 16, 54, 16, 84, 0, 1, 0, 2, 9, 246, 254, 187, 11, 184, 11, 34, 17,
+        198, 12, 28, 3, 82, 3, 222, 10, 66, 241, 0, 0, 69, 156, 64, 0, 66, 220, 128, 0, 69, 143,
+        192, 0, 39, 146, 76, 85, 3,
+    ];
+
+    let expected = (
+        eq(&76),
+        pat!(&CommandReply::BmsGetValues(pat!(BmsValues {
+            voltage: approx_eq(50.4),
+            charge_voltage: approx_eq(0.5),
+            current: approx_eq(-12.5),
+            current_ic: approx_eq(-12.25),
+            amp_hours: approx_eq(1.5),
+            watt_hours: approx_eq(75.25),
+            cell_count(): eq(3),
+            temp_count(): eq(2),
+            temp_ic: approx_eq(30.0),
+            temp_humidity_sensor: approx_eq(28.5),
+            humidity: approx_eq(45.5),
+            temp_cell_max: approx_eq(31.0),
+            soc: approx_eq(0.85),
+            soh: approx_eq(0.99),
+            can_id: eq(10),
+            amp_hours_charged_total: eq(120.5),
+            watt_hours_charged_total: eq(5000.0),
+            amp_hours_discharged_total: eq(110.25),
+            watt_hours_discharged_total: eq(4600.0),
+            pressure: approx_eq(101300.0),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+
+    let Ok((_, CommandReply::BmsGetValues(values))) = vesc::decode(&input) else {
+        panic!("expected a BMS values reply");
+    };
+    assert_that!(
+        values.cell_voltages(),
+        elements_are![approx_eq(4.2), approx_eq(4.15), approx_eq(4.18)]
+    );
+    assert_that!(
+        values.cell_balancing(),
+        elements_are![eq(&false), eq(&true), eq(&false)]
+    );
+    assert_that!(
+        values.temps(),
+        elements_are![approx_eq(25.5), approx_eq(-3.25)]
+    );
+}
+
+#[test]
+fn decode_bms_get_values_without_totals() {
+    let input = [
+        2, 53, 96, 3, 1, 11, 0, 0, 7, 161, 32, 255, 65, 67, 224, 255, 69, 20, 112, 0, 0, 5, 220, 0,
+        1, 37, 242, 3, 16, 104, 16, 54, 16, 84, 0, 1, 0, 2, 9, 246, 254, 187, 11, 184, 11, 34, 17,
+        198, 12, 28, 3, 82, 3, 222, 10, 223, 153, 3,
+    ];
+
+    let expected = (
+        eq(&58),
+        pat!(&CommandReply::BmsGetValues(pat!(BmsValues {
+            cell_count(): eq(3),
+            can_id: eq(10),
+            amp_hours_charged_total: eq(0.0),
+            pressure: eq(0.0),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_bms_get_values_too_many_cells() {
+    let input = [
+        2, 26, 96, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 51, 69,
+        205, 3,
+    ];
+    assert_that!(
+        vesc::decode(&input),
+        err(eq(&DecodeError::CapacityExceeded))
+    );
+}
+
+#[test]
+fn decode_bms_get_values_too_many_temps() {
+    let input = [
+        2, 27, 96, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 51,
+        131, 151, 3,
+    ];
+    assert_that!(
+        vesc::decode(&input),
+        err(eq(&DecodeError::CapacityExceeded))
+    );
+}
+
+#[test]
+fn decode_erase_new_app_hw() {
+    let input = [2, 2, 103, 1, 130, 156, 3];
+    let expected = (eq(&7), pat!(&CommandReply::EraseNewAppHw(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_write_new_app_data_hw() {
+    let input = [2, 6, 104, 1, 0, 0, 1, 134, 58, 116, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::WriteNewAppDataHw(eq(true), eq(390))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_erase_bootloader_hw() {
+    let input = [2, 2, 105, 0, 177, 178, 3];
+    let expected = (eq(&7), pat!(&CommandReply::EraseBootloaderHw(eq(false))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_erase_new_app_all_can_hw() {
+    let input = [2, 2, 107, 1, 199, 241, 3];
+    let expected = (eq(&7), pat!(&CommandReply::EraseNewAppAllCanHw(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_write_new_app_data_all_can_hw() {
+    let input = [2, 6, 108, 0, 0, 0, 0, 6, 52, 61, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::WriteNewAppDataAllCanHw(eq(false), eq(6))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_erase_bootloader_all_can_hw() {
+    let input = [2, 2, 109, 1, 109, 87, 3];
+    let expected = (
+        eq(&7),
+        pat!(&CommandReply::EraseBootloaderAllCanHw(eq(true))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_stats() {
+    let input = [
+        2, 17, 128, 0, 0, 4, 3, 64, 176, 0, 0, 65, 68, 0, 0, 68, 225, 0, 0, 247, 224, 3,
+    ];
+
+    let expected = (
+        eq(&22),
+        pat!(&CommandReply::GetStats(pat!(StatsValues {
+            speed_avg: eq(5.5),
+            speed_max: eq(12.25),
+            power_avg: eq(0.0),
+            count_time: eq(1800.0),
+            distance(): eq(9900.0),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_reset_stats() {
+    let input = [2, 1, 129, 129, 169, 3];
+    let expected = (eq(&6), pat!(&CommandReply::ResetStats));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_lisp_read_code() {
+    let input = [
+        2, 21, 130, 0, 0, 3, 232, 0, 0, 2, 0, 40, 100, 101, 102, 105, 110, 101, 32, 97, 32, 49, 41,
+        194, 100, 3,
+    ];
+
+    let Ok((size, CommandReply::LispReadCode(total_len, offset, data))) = vesc::decode(&input)
+    else {
+        panic!("expected a LispBM code reply");
+    };
+    assert_that!(size, eq(26));
+    assert_that!(total_len, eq(1000));
+    assert_that!(offset, eq(512));
+    assert_that!(data.as_slice(), eq(b"(define a 1)"));
+}
+
+#[test]
+fn decode_lisp_write_code() {
+    let input = [2, 6, 131, 1, 0, 0, 2, 0, 214, 243, 3];
+    let expected = (
+        eq(&11),
+        pat!(&CommandReply::LispWriteCode(eq(true), eq(512))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_lisp_erase_code() {
+    let input = [2, 2, 132, 1, 199, 125, 3];
+    let expected = (eq(&7), pat!(&CommandReply::LispEraseCode(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_lisp_set_running() {
+    let input = [2, 2, 133, 0, 228, 109, 3];
+    let expected = (eq(&7), pat!(&CommandReply::LispSetRunning(eq(false))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_lisp_get_stats() {
+    let input = [
+        2, 31, 134, 4, 210, 2, 38, 11, 184, 0, 225, 116, 0, 115, 112, 101, 101, 100, 0, 65, 72, 0,
+        0, 99, 111, 117, 110, 116, 0, 192, 64, 0, 0, 163, 138, 3,
+    ];
+
+    let Ok((size, CommandReply::LispGetStats(stats))) = vesc::decode(&input) else {
+        panic!("expected a LispBM stats reply");
+    };
+    assert_that!(size, eq(36));
+    assert_that!(
+        stats,
+        pat!(LispStats {
+            cpu_use: approx_eq(12.34),
+            heap_use: approx_eq(5.5),
+            mem_use: approx_eq(30.0),
+            stack_use: approx_eq(2.25),
+            ..
+        })
+    );
+    assert_that!(stats.done_ctx_r(), eq("t"));
+    assert_that!(
+        stats.bindings().collect::<Vec<_>>(),
+        elements_are![eq(&("speed", 12.5)), eq(&("count", -3.0))]
+    );
+}
+
+#[test]
+fn decode_lisp_get_stats_without_bindings() {
+    let input = [2, 10, 134, 0, 100, 0, 200, 1, 44, 1, 144, 0, 91, 218, 3];
+
+    let Ok((_, CommandReply::LispGetStats(stats))) = vesc::decode(&input) else {
+        panic!("expected a LispBM stats reply");
+    };
+    assert_that!(stats.done_ctx_r(), eq(""));
+    assert_that!(stats.bindings().next(), none());
+}
+
+#[test]
+fn decode_lisp_get_stats_unterminated_string() {
+    let input = [
+        2, 15, 134, 0, 100, 0, 200, 1, 44, 1, 144, 110, 111, 45, 110, 117, 108, 78, 223, 3,
+    ];
+    assert_that!(vesc::decode(&input), err(eq(&DecodeError::InvalidFrame)));
+}
+
+#[test]
+fn decode_lisp_print() {
+    let input = [2, 5, 135, 62, 32, 51, 0, 29, 183, 3];
+
+    let Ok((size, CommandReply::LispPrint(text))) = vesc::decode(&input) else {
+        panic!("expected a LispBM print reply");
+    };
+    assert_that!(size, eq(10));
+    assert_that!(text.as_str(), ok(eq("> 3")));
+}
+
+#[test]
+fn decode_file_list() {
+    let input = [
+        2, 22, 140, 1, 108, 111, 103, 0, 1, 0, 0, 0, 0, 97, 46, 99, 115, 118, 0, 0, 0, 0, 4, 210,
+        82, 217, 3,
+    ];
+
+    let Ok((size, CommandReply::FileList(list))) = vesc::decode(&input) else {
+        panic!("expected a file list reply");
+    };
+    assert_that!(size, eq(27));
+    assert_that!(list.has_more, eq(true));
+    assert_that!(
+        list.entries().collect::<Vec<_>>(),
+        elements_are![
+            eq(&FileEntry {
+                name: "log",
+                is_dir: true,
+                size: 0
+            }),
+            eq(&FileEntry {
+                name: "a.csv",
+                is_dir: false,
+                size: 1234
+            }),
+        ]
+    );
+}
+
+#[test]
+fn decode_file_list_truncated_entry() {
+    let input = [2, 5, 140, 0, 97, 0, 0, 5, 160, 3];
+    assert_that!(vesc::decode(&input), err(eq(&DecodeError::InvalidFrame)));
+}
+
+#[test]
+fn decode_file_read() {
+    let input = [2, 11, 141, 0, 0, 0, 4, 0, 0, 0, 6, 5, 6, 78, 127, 3];
+
+    let Ok((size, CommandReply::FileRead(chunk))) = vesc::decode(&input) else {
+        panic!("expected a file read reply");
+    };
+    assert_that!(size, eq(16));
+    assert_that!(chunk.offset, eq(4));
+    assert_that!(chunk.size, eq(6));
+    assert_that!(chunk.data.as_slice(), eq(&[5, 6]));
+}
+
+#[test]
+fn decode_file_write() {
+    let input = [2, 6, 142, 0, 0, 0, 4, 1, 136, 102, 3];
+    let expected = (eq(&11), pat!(&CommandReply::FileWrite(eq(true), eq(4))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_file_mkdir() {
+    let input = [2, 2, 143, 1, 27, 135, 3];
+    let expected = (eq(&7), pat!(&CommandReply::FileMkdir(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_file_remove() {
+    let input = [2, 2, 144, 0, 24, 235, 3];
+    let expected = (eq(&7), pat!(&CommandReply::FileRemove(eq(false))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_log_data_f32() {
+    let input = [2, 11, 148, 0, 1, 63, 192, 0, 0, 192, 16, 0, 0, 127, 63, 3];
+
+    let Ok((size, CommandReply::LogDataF32(samples))) = vesc::decode(&input) else {
+        panic!("expected single precision log samples");
+    };
+    assert_that!(size, eq(16));
+    assert_that!(samples.first_field, eq(1));
+    assert_that!(samples.len(), eq(2));
+    assert_that!(samples.get(2), none());
+    assert_that!(
+        samples.iter().collect::<Vec<_>>(),
+        elements_are![eq(&1.5), eq(&-2.25)]
+    );
+}
+
+#[test]
+fn decode_log_data_f32_partial_sample() {
+    let input = [2, 6, 148, 0, 0, 1, 2, 3, 169, 52, 3];
+    assert_that!(vesc::decode(&input), err(eq(&DecodeError::InvalidFrame)));
+}
+
+#[test]
+fn decode_get_gnss() {
+    let input = [
+        2, 43, 150, 3, 255, 8, 59, 205, 13, 199, 209, 62, 0, 253, 126, 19, 55, 6, 52, 160, 0, 65,
+        228, 0, 0, 63, 160, 0, 0, 63, 76, 204, 205, 2, 179, 44, 149, 7, 234, 10, 19, 63, 0, 0, 0,
+        211, 87, 3,
+    ];
+
+    let expected = (
+        eq(&48),
+        pat!(&CommandReply::GetGnss(pat!(GnssData {
+            latitude: approx_eq(59.3293235),
+            longitude: approx_eq(-18.0685808),
+            height: eq(28.5),
+            speed: eq(1.25),
+            hdop: approx_eq(0.8),
+            ms_today: eq(45296789),
+            year: eq(2026),
+            month: eq(10),
+            day: eq(19),
+            age: eq(0.5),
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_get_gnss_selective() {
+    let input = [
+        2, 12, 150, 0, 130, 253, 126, 19, 55, 6, 52, 160, 0, 10, 247, 73, 3,
+    ];
+
+    let expected = (
+        eq(&17),
+        pat!(&CommandReply::GetGnss(pat!(GnssData {
+            latitude: eq(0.0),
+            longitude: approx_eq(-18.0685808),
+            month: eq(10),
+            age: eq(0.0),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_log_data_f64() {
+    let input = [
+        2, 19, 151, 0, 4, 61, 204, 204, 205, 176, 204, 204, 205, 66, 40, 0, 0, 0, 0, 0, 0, 13, 171,
+        3,
+    ];
+
+    let Ok((size, CommandReply::LogDataF64(samples))) = vesc::decode(&input) else {
+        panic!("expected double precision log samples");
+    };
+    assert_that!(size, eq(24));
+    assert_that!(samples.first_field, eq(4));
+    assert_that!(
+        samples.iter().collect::<Vec<_>>(),
+        elements_are![near(0.1, 1e-15), eq(&42.0)]
+    );
+}
+
+#[test]
+fn decode_can_update_baud_all() {
+    let input = [2, 2, 158, 1, 43, 197, 3];
+    let expected = (eq(&7), pat!(&CommandReply::CanUpdateBaudAll(eq(true))));
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_long_frame() {
+    let input = [3, 0, 11, 50, 0, 0, 1, 128, 0, 0, 4, 210, 1, 176, 230, 49, 3];
+
+    let expected = (
+        eq(&17),
+        pat!(&CommandReply::GetValuesSelective(pat!(Values {
+            rpm: approx_eq(1234.0),
+            voltage_in: approx_eq(43.2),
+            ..
+        }))),
+    );
+    assert_that!(vesc::decode(&input), ok(expected));
+}
+
+#[test]
+fn decode_long_frame_incomplete_data() {
+    let input = [3, 0, 11, 50, 0, 0, 1, 128, 0, 0, 4, 210, 1, 176, 230, 49, 3];
+    let expected = &DecodeError::IncompleteData;
+
+    for i in 1..input.len() {
+        assert_that!(vesc::decode(&input[..i]), err(eq(expected)));
+    }
+}
+
 #[test]
 fn decode_incomplete_data() {
     let input = [
@@ -0,0 +1,138 @@
+use googletest::prelude::*;
+
+use vesc::{Command, CommandReply, ConfigXmlChunk, ConfigXmlDownload, DownloadError, ReplyData};
+
+/// Replies carrying data of up to the default buffer length.
+type Reply = CommandReply;
+
+fn chunk(index: u8, total_len: u32, offset: u32, data: &[u8]) -> CommandReply {
+    CommandReply::GetCustomConfigXml(ConfigXmlChunk {
+        index,
+        total_len,
+        offset,
+        data: ReplyData::new(data).unwrap(),
+    })
+}
+
+#[test]
+fn config_xml_download_collects_chunks() {
+    let xml = (0..10).collect::<Vec<u8>>();
+    let mut buf = [0u8; 16];
+    let mut download = ConfigXmlDownload::new(2, &mut buf).chunk_size(4);
+
+    assert_that!(
+        download.next_command(),
+        some(pat!(Command::GetCustomConfigXml(eq(2), eq(0), eq(0))))
+    );
+    download.acknowledge(&chunk(2, 10, 0, &[])).unwrap();
+    assert_that!(download.total_len(), some(eq(10)));
+
+    for (offset, len) in [(0, 4), (4, 4), (8, 2)] {
+        assert_that!(download.is_finished(), eq(false));
+        assert_that!(
+            download.next_command(),
+            some(pat!(Command::GetCustomConfigXml(
+                eq(2),
+                eq(offset),
+                eq(len)
+            )))
+        );
+        let end = (offset + len) as usize;
+        download
+            .acknowledge(&chunk(2, 10, offset, &xml[offset as usize..end]))
+            .unwrap();
+    }
+
+    assert_that!(download.next_command(), none());
+    assert_that!(download.is_finished(), eq(true));
+    assert_that!(download.downloaded_len(), eq(10));
+    assert_that!(download.xml(), eq(&xml[..]));
+}
+
+#[test]
+fn config_xml_download_empty_xml() {
+    let mut buf = [0u8; 16];
+    let mut download = ConfigXmlDownload::new(0, &mut buf);
+
+    download.acknowledge(&chunk(0, 0, 0, &[])).unwrap();
+
+    assert_that!(download.next_command(), none());
+    assert_that!(download.xml(), is_empty());
+}
+
+#[test]
+fn config_xml_download_buffer_too_small() {
+    let mut buf = [0u8; 8];
+    let mut download = ConfigXmlDownload::new(0, &mut buf);
+
+    assert_that!(
+        download.acknowledge(&chunk(0, 9, 0, &[])),
+        err(eq(&DownloadError::BufferTooSmall { required: 9 }))
+    );
+    assert_that!(download.total_len(), none());
+}
+
+#[test]
+fn config_xml_download_offset_mismatch() {
+    let mut buf = [0u8; 16];
+    let mut download = ConfigXmlDownload::new(0, &mut buf).chunk_size(4);
+    download.acknowledge(&chunk(0, 8, 0, &[])).unwrap();
+
+    assert_that!(
+        download.acknowledge(&chunk(0, 8, 4, &[1, 2, 3, 4])),
+        err(eq(&DownloadError::OffsetMismatch {
+            expected: 0,
+            actual: 4
+        }))
+    );
+
+    // The pending step can be retried.
+    download
+        .acknowledge(&chunk(0, 8, 0, &[1, 2, 3, 4]))
+        .unwrap();
+    assert_that!(download.downloaded_len(), eq(4));
+}
+
+#[test]
+fn config_xml_download_unexpected_reply() {
+    let mut buf = [0u8; 16];
+    let mut download = ConfigXmlDownload::new(1, &mut buf);
+
+    assert_that!(
+        download.acknowledge(&Reply::SetCustomConfig),
+        err(eq(&DownloadError::UnexpectedReply))
+    );
+    assert_that!(
+        download.acknowledge(&chunk(0, 8, 0, &[])),
+        err(eq(&DownloadError::UnexpectedReply))
+    );
+
+    download.acknowledge(&chunk(1, 4, 0, &[])).unwrap();
+    assert_that!(
+        download.acknowledge(&chunk(1, 5, 0, &[1, 2, 3, 4])),
+        err(eq(&DownloadError::UnexpectedReply))
+    );
+    assert_that!(
+        download.acknowledge(&chunk(1, 4, 0, &[1, 2, 3, 4, 5])),
+        err(eq(&DownloadError::UnexpectedReply))
+    );
+}
+
+#[test]
+fn config_xml_download_rejects_empty_chunk() {
+    let mut buf = [0u8; 16];
+    let mut download = ConfigXmlDownload::new(0, &mut buf).chunk_size(4);
+    download.acknowledge(&chunk(0, 8, 0, &[])).unwrap();
+
+    assert_that!(
+        download.acknowledge(&chunk(0, 8, 0, &[])),
+        err(eq(&DownloadError::UnexpectedReply))
+    );
+    assert_that!(download.downloaded_len(), eq(0));
+
+    // The pending step can be retried.
+    download
+        .acknowledge(&chunk(0, 8, 0, &[1, 2, 3, 4]))
+        .unwrap();
+    assert_that!(download.downloaded_len(), eq(4));
+}
@@ -345,3 +345,40 @@ fn decoder_iterator_collects_all_valid_packets() {
     let replies = decoder.by_ref().collect::<Vec<_>>();
     assert_that!(replies, expected);
 }
+
+#[test]
+fn decoder_decodes_plot_stream() {
+    let input = [
+        2, 2, 78, 1, 62, 226, 3, 2, 9, 76, 63, 0, 0, 0, 192, 64, 0, 0, 219, 222, 3,
+    ];
+
+    let mut decoder = Decoder::default();
+    decoder.feed(&input).unwrap();
+
+    assert_that!(
+        decoder.next(),
+        some(pat!(CommandReply::PlotSetGraph(eq(1))))
+    );
+    assert_that!(
+        decoder.next(),
+        some(pat!(CommandReply::PlotData(eq(0.5), eq(-3.0))))
+    );
+    assert_that!(decoder.next(), none());
+}
+
+#[test]
+fn decoder_decodes_max_size_long_frame() {
+    let mut input = vec![3, 2, 0, 36];
+    input.extend([0xaa; 511]);
+    input.extend([247, 103, 3]);
+
+    let mut decoder = Decoder::default();
+    assert_that!(decoder.feed(&input), ok(eq(&518)));
+
+    let Some(CommandReply::CustomAppData(data)) = decoder.next() else {
+        panic!("expected a custom app data reply");
+    };
+    assert_that!(*data, each(eq(&0xaa)));
+    assert_that!(data.len(), eq(511));
+    assert_that!(decoder.next(), none());
+}
@@ -0,0 +1,181 @@
+use googletest::prelude::*;
+
+use vesc::{
+    Command, CommandReply, FileChunk, FileDownload, FileTransferError, FileUpload, ReplyData,
+};
+
+/// Replies carrying data of up to the default buffer length.
+type Reply = CommandReply;
+
+fn chunk(offset: u32, size: i32, data: &[u8]) -> CommandReply {
+    CommandReply::FileRead(FileChunk {
+        offset,
+        size,
+        data: ReplyData::new(data).unwrap(),
+    })
+}
+
+#[test]
+fn file_download_collects_chunks() {
+    let file = (0..10).collect::<Vec<u8>>();
+    let mut buf = [0u8; 16];
+    let mut download = FileDownload::new("/a", &mut buf);
+
+    for (offset, len) in [(0, 4), (4, 4), (8, 2)] {
+        assert_that!(download.is_finished(), eq(false));
+        assert_that!(
+            download.next_command(),
+            some(pat!(Command::FileRead(eq("/a"), eq(offset))))
+        );
+        let end = (offset + len) as usize;
+        download
+            .acknowledge(&chunk(offset, 10, &file[offset as usize..end]))
+            .unwrap();
+        assert_that!(download.size(), some(eq(10)));
+    }
+
+    assert_that!(download.next_command(), none());
+    assert_that!(download.is_finished(), eq(true));
+    assert_that!(download.data(), eq(&file[..]));
+}
+
+#[test]
+fn file_download_empty_file() {
+    let mut buf = [0u8; 16];
+    let mut download = FileDownload::new("/a", &mut buf);
+
+    download.acknowledge(&chunk(0, 0, &[])).unwrap();
+
+    assert_that!(download.next_command(), none());
+    assert_that!(download.data(), is_empty());
+}
+
+#[test]
+fn file_download_resume() {
+    let mut buf = [0u8; 16];
+    buf[..4].copy_from_slice(&[1, 2, 3, 4]);
+    let mut download = FileDownload::new("/a", &mut buf).resume_from(4);
+
+    assert_that!(
+        download.next_command(),
+        some(pat!(Command::FileRead(eq("/a"), eq(4))))
+    );
+    download.acknowledge(&chunk(4, 6, &[5, 6])).unwrap();
+
+    assert_that!(download.is_finished(), eq(true));
+    assert_that!(download.data(), eq(&[1, 2, 3, 4, 5, 6]));
+}
+
+#[test]
+fn file_download_errors() {
+    let mut buf = [0u8; 8];
+    let mut download = FileDownload::new("/a", &mut buf);
+
+    assert_that!(
+        download.acknowledge(&chunk(0, -1, &[])),
+        err(eq(&FileTransferError::ReadFailed))
+    );
+    assert_that!(
+        download.acknowledge(&chunk(0, 9, &[])),
+        err(eq(&FileTransferError::BufferTooSmall { required: 9 }))
+    );
+    assert_that!(
+        download.acknowledge(&chunk(4, 8, &[1])),
+        err(eq(&FileTransferError::OffsetMismatch {
+            expected: 0,
+            actual: 4
+        }))
+    );
+    assert_that!(
+        download.acknowledge(&chunk(0, 8, &[])),
+        err(eq(&FileTransferError::UnexpectedReply))
+    );
+    assert_that!(
+        download.acknowledge(&Reply::FileWrite(true, 0)),
+        err(eq(&FileTransferError::UnexpectedReply))
+    );
+
+    // The pending step can be retried.
+    download.acknowledge(&chunk(0, 8, &[1, 2])).unwrap();
+    assert_that!(
+        download.acknowledge(&chunk(2, 7, &[3])),
+        err(eq(&FileTransferError::UnexpectedReply))
+    );
+    assert_that!(download.downloaded_len(), eq(2));
+}
+
+#[test]
+fn file_upload_sends_chunks() {
+    let file = (0..10).collect::<Vec<u8>>();
+    let mut upload = FileUpload::new("/a", &file).unwrap().chunk_size(4);
+
+    for (offset, len) in [(0, 4), (4, 4), (8, 2)] {
+        assert_that!(upload.is_finished(), eq(false));
+        let Some(Command::FileWrite(params)) = upload.next_command() else {
+            panic!("expected a file write command");
+        };
+        assert_that!(params.path, eq("/a"));
+        assert_that!(params.offset, eq(offset));
+        assert_that!(params.size, eq(10));
+        assert_that!(
+            params.data,
+            eq(&file[offset as usize..(offset + len) as usize])
+        );
+        upload.acknowledge(&Reply::FileWrite(true, offset)).unwrap();
+    }
+
+    assert_that!(upload.next_command(), none());
+    assert_that!(upload.is_finished(), eq(true));
+    assert_that!(upload.uploaded_len(), eq(10));
+}
+
+#[test]
+fn file_upload_empty_file() {
+    let mut upload = FileUpload::new("/a", &[]).unwrap();
+
+    let Some(Command::FileWrite(params)) = upload.next_command() else {
+        panic!("expected a file write command");
+    };
+    assert_that!(params.data, is_empty());
+    upload.acknowledge(&Reply::FileWrite(true, 0)).unwrap();
+
+    assert_that!(upload.next_command(), none());
+}
+
+#[test]
+fn file_upload_resume() {
+    let file = (0..10).collect::<Vec<u8>>();
+    let mut upload = FileUpload::new("/a", &file).unwrap().resume_from(8);
+
+    let Some(Command::FileWrite(params)) = upload.next_command() else {
+        panic!("expected a file write command");
+    };
+    assert_that!(params.offset, eq(8));
+    assert_that!(params.data, eq(&[8, 9]));
+    upload.acknowledge(&Reply::FileWrite(true, 8)).unwrap();
+
+    assert_that!(upload.is_finished(), eq(true));
+}
+
+#[test]
+fn file_upload_errors() {
+    let file = [0u8; 10];
+    let mut upload = FileUpload::new("/a", &file).unwrap();
+
+    assert_that!(
+        upload.acknowledge(&Reply::FileWrite(true, 4)),
+        err(eq(&FileTransferError::OffsetMismatch {
+            expected: 0,
+            actual: 4
+        }))
+    );
+    assert_that!(
+        upload.acknowledge(&Reply::FileWrite(false, 0)),
+        err(eq(&FileTransferError::WriteFailed { offset: 0 }))
+    );
+    assert_that!(
+        upload.acknowledge(&Reply::FileMkdir(true)),
+        err(eq(&FileTransferError::UnexpectedReply))
+    );
+    assert_that!(upload.uploaded_len(), eq(0));
+}
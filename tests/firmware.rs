@@ -0,0 +1,444 @@
+use googletest::prelude::*;
+
+use vesc::{Command, CommandReply, FirmwareUpload, HwType, UploadError};
+
+/// Replies carrying data of up to the default buffer length.
+type Reply = CommandReply;
+
+#[test]
+fn firmware_upload_yields_erase_and_write_commands() {
+    let image = (0..1000).map(|i| i as u8).collect::<Vec<_>>();
+    let mut upload = FirmwareUpload::new(&image).unwrap();
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::EraseNewApp(eq(1006))))
+    );
+    upload.acknowledge(&Reply::EraseNewApp(true)).unwrap();
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::WriteNewAppData(
+            eq(0),
+            eq(&[0, 0, 3, 232, 63, 150][..])
+        )))
+    );
+    upload
+        .acknowledge(&Reply::WriteNewAppData(true, 0))
+        .unwrap();
+
+    for (offset, chunk) in [(6, &image[..384]), (390, &image[384..768])] {
+        assert_that!(
+            upload.next_command(),
+            some(pat!(Command::WriteNewAppData(eq(offset), eq(chunk))))
+        );
+        upload
+            .acknowledge(&Reply::WriteNewAppData(true, offset))
+            .unwrap();
+        assert_that!(upload.is_finished(), eq(false));
+    }
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::WriteNewAppData(eq(774), eq(&image[768..]))))
+    );
+    upload
+        .acknowledge(&Reply::WriteNewAppData(true, 774))
+        .unwrap();
+
+    assert_that!(upload.next_command(), none());
+    assert_that!(upload.is_finished(), eq(true));
+    assert_that!(upload.uploaded_len(), eq(upload.total_len()));
+}
+
+#[test]
+fn firmware_upload_all_can() {
+    let image = [1, 2, 3, 4];
+    let mut upload = FirmwareUpload::new(&image).unwrap().all_can().chunk_size(3);
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::EraseNewAppAllCan(eq(10))))
+    );
+    upload.acknowledge(&Reply::EraseNewAppAllCan(true)).unwrap();
+
+    for (offset, chunk) in [(0, &[0, 0, 0, 4, 13, 3][..]), (6, &[1, 2, 3]), (9, &[4])] {
+        assert_that!(
+            upload.next_command(),
+            some(pat!(Command::WriteNewAppDataAllCan(eq(offset), eq(chunk))))
+        );
+        upload
+            .acknowledge(&Reply::WriteNewAppDataAllCan(true, offset))
+            .unwrap();
+    }
+
+    assert_that!(upload.next_command(), none());
+}
+
+#[test]
+fn firmware_upload_hw() {
+    let image = [1, 2, 3, 4];
+    let mut upload = FirmwareUpload::new(&image)
+        .unwrap()
+        .hw(HwType::VescBms)
+        .chunk_size(4);
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::EraseNewAppHw(eq(HwType::VescBms), eq(10))))
+    );
+    let result = upload.acknowledge(&Reply::EraseNewApp(true));
+    assert_that!(result, err(eq(&UploadError::UnexpectedReply)));
+    upload.acknowledge(&Reply::EraseNewAppHw(true)).unwrap();
+
+    for (offset, chunk) in [(0, &[0, 0, 0, 4, 13, 3][..]), (6, &[1, 2, 3, 4])] {
+        assert_that!(
+            upload.next_command(),
+            some(pat!(Command::WriteNewAppDataHw(
+                eq(HwType::VescBms),
+                eq(offset),
+                eq(chunk)
+            )))
+        );
+        upload
+            .acknowledge(&Reply::WriteNewAppDataHw(true, offset))
+            .unwrap();
+    }
+
+    assert_that!(upload.next_command(), none());
+}
+
+#[test]
+fn firmware_upload_hw_all_can_ignores_lzo() {
+    let image = [7u8; 100];
+    let mut buf = [0u8; 100];
+    let mut upload = FirmwareUpload::new(&image)
+        .unwrap()
+        .all_can()
+        .hw(HwType::CustomModule)
+        .lzo(&mut buf);
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::EraseNewAppAllCanHw(
+            eq(HwType::CustomModule),
+            eq(106)
+        )))
+    );
+    upload
+        .acknowledge(&Reply::EraseNewAppAllCanHw(true))
+        .unwrap();
+    upload
+        .acknowledge(&Reply::WriteNewAppDataAllCanHw(true, 0))
+        .unwrap();
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::WriteNewAppDataAllCanHw(
+            eq(HwType::CustomModule),
+            eq(6),
+            eq(&image[..])
+        )))
+    );
+    upload
+        .acknowledge(&Reply::WriteNewAppDataAllCanHw(true, 6))
+        .unwrap();
+    assert_that!(upload.is_finished(), eq(true));
+}
+
+#[test]
+fn firmware_upload_chunks_are_encoded_as_long_frames() {
+    let image = [0u8; 1000];
+    let mut upload = FirmwareUpload::new(&image).unwrap();
+    let mut buf = [0u8; 512];
+
+    upload.acknowledge(&Reply::EraseNewApp(true)).unwrap();
+    upload
+        .acknowledge(&Reply::WriteNewAppData(true, 0))
+        .unwrap();
+
+    let size = vesc::encode(upload.next_command().unwrap(), &mut buf).unwrap();
+    assert_that!(size, eq(395));
+    assert_that!(buf[..8], eq([3, 1, 133, 3, 0, 0, 0, 6]));
+}
+
+#[test]
+fn firmware_upload_erase_failed() {
+    let mut upload = FirmwareUpload::new(&[1, 2, 3]).unwrap();
+
+    let result = upload.acknowledge(&Reply::EraseNewApp(false));
+    assert_that!(result, err(eq(&UploadError::EraseFailed)));
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::EraseNewApp(eq(9))))
+    );
+}
+
+#[test]
+fn firmware_upload_write_failed() {
+    let mut upload = FirmwareUpload::new(&[1, 2, 3]).unwrap();
+    upload.acknowledge(&Reply::EraseNewApp(true)).unwrap();
+
+    let result = upload.acknowledge(&Reply::WriteNewAppData(false, 0));
+    assert_that!(result, err(eq(&UploadError::WriteFailed { offset: 0 })));
+    assert_that!(upload.uploaded_len(), eq(0));
+}
+
+#[test]
+fn firmware_upload_offset_mismatch() {
+    let mut upload = FirmwareUpload::new(&[1, 2, 3]).unwrap();
+    upload.acknowledge(&Reply::EraseNewApp(true)).unwrap();
+
+    let result = upload.acknowledge(&Reply::WriteNewAppData(true, 6));
+    let expected = &UploadError::OffsetMismatch {
+        expected: 0,
+        actual: 6,
+    };
+    assert_that!(result, err(eq(expected)));
+}
+
+#[test]
+fn firmware_upload_unexpected_reply() {
+    let mut upload = FirmwareUpload::new(&[1, 2, 3]).unwrap();
+
+    let result = upload.acknowledge(&Reply::WriteNewAppData(true, 0));
+    assert_that!(result, err(eq(&UploadError::UnexpectedReply)));
+
+    let result = upload.acknowledge(&Reply::EraseNewAppAllCan(true));
+    assert_that!(result, err(eq(&UploadError::UnexpectedReply)));
+}
+
+#[test]
+fn firmware_upload_lzo_compresses_chunks() {
+    let image = b"VESC firmware ".repeat(100);
+    let mut buf = [0u8; 384];
+    let mut upload = FirmwareUpload::new(&image).unwrap().lzo(&mut buf);
+
+    upload.acknowledge(&Reply::EraseNewApp(true)).unwrap();
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::WriteNewAppData(eq(0), len(eq(6)))))
+    );
+    upload
+        .acknowledge(&Reply::WriteNewAppData(true, 0))
+        .unwrap();
+
+    let mut decompressed = vec![];
+    while let Some(command) = upload.next_command() {
+        let Command::WriteNewAppDataLzo(offset, len, data) = command else {
+            panic!("unexpected command: {command:?}");
+        };
+        assert_that!(offset as usize, eq(decompressed.len() + 6));
+        assert_that!(data.len(), lt(len as usize));
+
+        decompressed.extend(lzo1x_decompress(data));
+        assert_that!(decompressed.len(), eq(offset as usize - 6 + len as usize));
+
+        upload
+            .acknowledge(&Reply::WriteNewAppDataLzo(true, offset))
+            .unwrap();
+    }
+    assert_that!(decompressed, eq(&image));
+}
+
+#[test]
+fn firmware_upload_lzo_all_can() {
+    let image = [7u8; 100];
+    let mut buf = [0u8; 100];
+    let mut upload = FirmwareUpload::new(&image).unwrap().all_can().lzo(&mut buf);
+
+    upload.acknowledge(&Reply::EraseNewAppAllCan(true)).unwrap();
+    upload
+        .acknowledge(&Reply::WriteNewAppDataAllCan(true, 0))
+        .unwrap();
+
+    let Some(Command::WriteNewAppDataAllCanLzo(6, 100, data)) = upload.next_command() else {
+        panic!("unexpected command: {:?}", upload.next_command());
+    };
+    assert_that!(lzo1x_decompress(data), eq(&image));
+
+    let result = upload.acknowledge(&Reply::WriteNewAppDataAllCan(true, 6));
+    assert_that!(result, err(eq(&UploadError::UnexpectedReply)));
+    upload
+        .acknowledge(&Reply::WriteNewAppDataAllCanLzo(true, 6))
+        .unwrap();
+    assert_that!(upload.is_finished(), eq(true));
+}
+
+#[test]
+fn firmware_upload_lzo_falls_back_to_raw_chunks() {
+    let image = pseudo_random_bytes(1000);
+    let mut buf = [0u8; 384];
+    let mut upload = FirmwareUpload::new(&image).unwrap().lzo(&mut buf);
+
+    upload.acknowledge(&Reply::EraseNewApp(true)).unwrap();
+    upload
+        .acknowledge(&Reply::WriteNewAppData(true, 0))
+        .unwrap();
+
+    assert_that!(
+        upload.next_command(),
+        some(pat!(Command::WriteNewAppData(eq(6), eq(&image[..384]))))
+    );
+}
+
+#[test]
+fn firmware_upload_lzo_round_trip() {
+    let mut images = vec![
+        vec![],
+        vec![1],
+        vec![1, 2, 3],
+        vec![0; 5000],
+        (0..5000).map(|i| (i / 7) as u8).collect(),
+        b"abcabcabcabd".repeat(300),
+    ];
+    let mut mixed = pseudo_random_bytes(300);
+    mixed.extend([0xAA; 40]);
+    mixed.extend(pseudo_random_bytes(5));
+    mixed.extend(&mixed.clone()[10..80]);
+    mixed.extend(pseudo_random_bytes(20));
+    mixed.extend([0x55; 600]);
+    images.push(mixed);
+
+    for image in images {
+        for chunk_size in [17, 384, 4096] {
+            let mut buf = vec![0u8; chunk_size];
+            let mut upload = FirmwareUpload::new(&image)
+                .unwrap()
+                .chunk_size(chunk_size)
+                .lzo(&mut buf);
+            upload.acknowledge(&Reply::EraseNewApp(true)).unwrap();
+            upload
+                .acknowledge(&Reply::WriteNewAppData(true, 0))
+                .unwrap();
+
+            let mut uploaded = vec![];
+            while let Some(command) = upload.next_command() {
+                let reply: Reply = match command {
+                    Command::WriteNewAppData(offset, data) => {
+                        uploaded.extend(data);
+                        CommandReply::WriteNewAppData(true, offset)
+                    }
+                    Command::WriteNewAppDataLzo(offset, len, data) => {
+                        let data = lzo1x_decompress(data);
+                        assert_that!(data.len(), eq(len as usize));
+                        uploaded.extend(data);
+                        CommandReply::WriteNewAppDataLzo(true, offset)
+                    }
+                    command => panic!("unexpected command: {command:?}"),
+                };
+                upload.acknowledge(&reply).unwrap();
+            }
+            assert_that!(uploaded, eq(&image));
+        }
+    }
+}
+
+fn pseudo_random_bytes(len: usize) -> Vec<u8> {
+    let mut state = 0x1234_5678u32;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect()
+}
+
+/// A straightforward port of `lzo1x_decompress_safe()` used by the VESC
+/// firmware, to verify the compressed chunks.
+fn lzo1x_decompress(input: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = vec![];
+    let mut ip = 0;
+    let mut next = || {
+        ip += 1;
+        input[ip - 1]
+    };
+    let extend = |t: usize, next: &mut dyn FnMut() -> u8| {
+        let mut t = t;
+        loop {
+            match next() {
+                0 => t += 255,
+                b => return t + b as usize,
+            }
+        }
+    };
+    let copy_match = |out: &mut Vec<u8>, distance: usize, len: usize| {
+        let start = out.len() - distance;
+        for i in 0..len {
+            out.push(out[start + i]);
+        }
+    };
+
+    // The number of literals copied by the previous instruction, which
+    // determines how instructions below 16 are interpreted.
+    let mut state;
+    let mut t = next() as usize;
+    if t > 17 {
+        t -= 17;
+        for _ in 0..t {
+            out.push(next());
+        }
+        state = if t < 4 { t } else { 4 };
+        t = next() as usize;
+    } else {
+        state = 0;
+    }
+
+    loop {
+        if t < 16 {
+            match state {
+                0 => {
+                    let len = if t == 0 { extend(15, &mut next) } else { t } + 3;
+                    for _ in 0..len {
+                        out.push(next());
+                    }
+                    state = 4;
+                    t = next() as usize;
+                    continue;
+                }
+                1..=3 => {
+                    let distance = 1 + (t >> 2) + ((next() as usize) << 2);
+                    copy_match(&mut out, distance, 2);
+                }
+                _ => {
+                    let distance = 2049 + (t >> 2) + ((next() as usize) << 2);
+                    copy_match(&mut out, distance, 3);
+                }
+            }
+            state = t & 3;
+        } else if t >= 64 {
+            let b = next() as usize;
+            copy_match(&mut out, 1 + ((t >> 2) & 7) + (b << 3), (t >> 5) + 1);
+            state = t & 3;
+        } else if t >= 32 {
+            let len = if t & 31 == 0 {
+                extend(31, &mut next)
+            } else {
+                t & 31
+            } + 2;
+            let (b0, b1) = (next() as usize, next() as usize);
+            copy_match(&mut out, 1 + (b0 >> 2) + (b1 << 6), len);
+            state = b0 & 3;
+        } else {
+            let len = if t & 7 == 0 {
+                extend(7, &mut next)
+            } else {
+                t & 7
+            } + 2;
+            let (b0, b1) = (next() as usize, next() as usize);
+            let distance = ((t & 8) << 11) + (b0 >> 2) + (b1 << 6);
+            if distance == 0 {
+                assert_that!(ip, eq(input.len()));
+                return out;
+            }
+            copy_match(&mut out, distance + 0x4000, len);
+            state = b0 & 3;
+        }
+        for _ in 0..state {
+            out.push(next());
+        }
+        t = next() as usize;
+    }
+}
@@ -0,0 +1,186 @@
+use googletest::prelude::*;
+
+use vesc::{
+    CAN_BROADCAST_ID, CanForward, CanMode, Command, CommandReply, EncodeError, TaggedReply, Values,
+    ValuesMask,
+};
+
+/// Replies carrying data of up to the default buffer length.
+type Reply = CommandReply;
+
+#[test]
+fn can_forward_to_single_controller() {
+    let mut buf = [0u8; 32];
+
+    let forward = CanForward::to(1, Command::SetRpm(1234));
+    let size = forward.encode_into(&mut buf).unwrap();
+
+    assert_that!(forward.targets(), eq(&[1]));
+    assert_that!(buf[..size], eq([2, 7, 34, 1, 8, 0, 0, 4, 210, 110, 99, 3]));
+}
+
+#[test]
+fn can_forward_to_all_controllers() {
+    let mut buf = [0u8; 32];
+
+    let forward = CanForward::to_all(&[1, 7], Command::SetRpm(1234));
+    let size = forward.encode_into(&mut buf).unwrap();
+
+    assert_that!(forward.targets(), eq(&[1, 7]));
+    assert_that!(
+        buf[..size],
+        eq([
+            2, 7, 34, 1, 8, 0, 0, 4, 210, 110, 99, 3, //
+            2, 7, 34, 7, 8, 0, 0, 4, 210, 227, 130, 3,
+        ])
+    );
+}
+
+#[test]
+fn can_forward_broadcast() {
+    let mut buf = [0u8; 32];
+
+    let forward = CanForward::broadcast(Command::Reboot);
+    let size = forward.encode_into(&mut buf).unwrap();
+
+    assert_that!(forward.targets(), eq(&[CAN_BROADCAST_ID]));
+    assert_that!(buf[..size], eq([2, 3, 34, 255, 29, 40, 197, 3]));
+}
+
+#[test]
+fn can_forward_buffer_too_small() {
+    let mut buf = [0u8; 20];
+
+    let forward = CanForward::to_all(&[1, 7], Command::SetRpm(1234));
+
+    assert_that!(
+        forward.encode_into(&mut buf),
+        err(eq(&EncodeError::BufferTooSmall))
+    );
+}
+
+#[test]
+fn can_forward_rejects_nested_forward() {
+    let mut buf = [0u8; 32];
+    let nested = Command::ForwardCan(2, &Command::Reboot);
+
+    assert_that!(
+        CanForward::to(1, nested).encode_into(&mut buf),
+        err(eq(&EncodeError::NestedForwardCan))
+    );
+    assert_that!(
+        vesc::encode(Command::ForwardCan(1, &nested), &mut buf),
+        err(eq(&EncodeError::NestedForwardCan))
+    );
+}
+
+#[test]
+fn can_forward_split() {
+    let forward = CanForward::to_all(&[1, 7], Command::SetRpm(1234));
+
+    let targets = forward
+        .split()
+        .map(|forward| forward.targets().to_vec())
+        .collect::<Vec<_>>();
+
+    assert_that!(targets, eq(&[vec![1], vec![7]]));
+}
+
+#[test]
+fn can_forward_tags_replies_by_payload() {
+    let forward = CanForward::to_all(&[3, 5, 7], Command::GetValues);
+    let replies = [5, 7, 3].map(|controller_id| {
+        Reply::GetValues(Values {
+            controller_id,
+            ..Default::default()
+        })
+    });
+
+    let tagged = forward
+        .tag_replies(replies)
+        .map(|tagged| tagged.controller_id)
+        .collect::<Vec<_>>();
+
+    assert_that!(tagged, eq(&[5, 7, 3]));
+}
+
+#[test]
+fn can_forward_tags_broadcast_replies_by_payload() {
+    let forward = CanForward::broadcast(Command::GetValuesSelective(
+        ValuesMask::RPM | ValuesMask::CONTROLLER_ID,
+    ));
+    let replies = [4, 9].map(|controller_id| {
+        Reply::GetValuesSelective(Values {
+            controller_id,
+            ..Default::default()
+        })
+    });
+
+    let tagged = forward
+        .tag_replies(replies)
+        .map(|tagged| tagged.controller_id)
+        .collect::<Vec<_>>();
+
+    assert_that!(tagged, eq(&[4, 9]));
+}
+
+#[test]
+fn can_forward_tags_replies_in_order() {
+    let forward = CanForward::to_all(&[3, 5], Command::SetCanMode(CanMode::Vesc, false, true));
+    let replies = [Reply::SetCanMode, Reply::SetCanMode, Reply::SetCanMode];
+
+    let tagged = forward
+        .tag_replies(replies)
+        .map(|tagged| tagged.controller_id)
+        .collect::<Vec<_>>();
+
+    assert_that!(tagged, eq(&[3, 5]));
+}
+
+#[test]
+fn can_forward_tags_broadcast_replies() {
+    let forward = CanForward::broadcast(Command::SetCanMode(CanMode::Vesc, false, true));
+    let replies = [Reply::SetCanMode, Reply::SetCanMode];
+
+    let tagged = forward
+        .tag_replies(replies)
+        .map(|tagged| tagged.controller_id)
+        .collect::<Vec<_>>();
+
+    assert_that!(tagged, eq(&[CAN_BROADCAST_ID, CAN_BROADCAST_ID]));
+}
+
+#[test]
+fn can_forward_tags_single_reply() {
+    let reply = Reply::SetCanMode;
+
+    assert_that!(
+        CanForward::to(3, Command::SetCanMode(CanMode::Vesc, false, true)).tag_reply(reply),
+        some(pat!(TaggedReply {
+            controller_id: eq(3),
+            reply: pat!(CommandReply::SetCanMode),
+        }))
+    );
+    assert_that!(
+        CanForward::to_all(&[3, 5], Command::SetCanMode(CanMode::Vesc, false, true))
+            .tag_reply(reply),
+        none()
+    );
+    assert_that!(
+        CanForward::broadcast(Command::SetCanMode(CanMode::Vesc, false, true)).tag_reply(reply),
+        none()
+    );
+}
+
+#[test]
+fn can_forward_tags_single_reply_by_payload() {
+    let reply = Reply::GetValues(Values {
+        controller_id: 9,
+        ..Default::default()
+    });
+
+    assert_that!(
+        CanForward::broadcast(Command::GetValues).tag_reply(reply),
+        some(field!(TaggedReply.controller_id, eq(9)))
+    );
+}
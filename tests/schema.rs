@@ -0,0 +1,267 @@
+#![cfg(feature = "std")]
+
+use googletest::prelude::*;
+
+use vesc::{ConfigError, ConfigSchema, ConfigValue, ConfigValues, ParamType, TxType};
+
+const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ConfigParams>
+    <Params>
+        <l_current_max>
+            <longName>Motor Current Max</longName>
+            <type>1</type>
+            <transmittable>1</transmittable>
+            <description>Maximum motor current.</description>
+            <valDouble>60</valDouble>
+            <vTx>9</vTx>
+            <suffix> A</suffix>
+        </l_current_max>
+        <l_temp_fet_start>
+            <longName>MOSFET Temp Cutoff Start</longName>
+            <type>1</type>
+            <valDouble>85</valDouble>
+            <vTx>7</vTx>
+            <vTxDoubleScale>10</vTxDoubleScale>
+        </l_temp_fet_start>
+        <l_erpm_start>
+            <longName>ERPM Limit Start</longName>
+            <type>1</type>
+            <valDouble>0.8</valDouble>
+            <vTx>3</vTx>
+            <vTxDoubleScale>1000</vTxDoubleScale>
+        </l_erpm_start>
+        <motor_type>
+            <longName>Motor Type</longName>
+            <type>4</type>
+            <valInt>2</valInt>
+            <vTx>1</vTx>
+            <enumNames>BLDC</enumNames>
+            <enumNames>DC</enumNames>
+            <enumNames>FOC</enumNames>
+        </motor_type>
+        <si_motor_poles>
+            <longName>Motor Poles</longName>
+            <type>2</type>
+            <valInt>14</valInt>
+            <vTx>2</vTx>
+        </si_motor_poles>
+        <foc_sample_v0_v7>
+            <longName>Sample in v0 and v7</longName>
+            <type>5</type>
+            <valInt>0</valInt>
+            <vTx>1</vTx>
+        </foc_sample_v0_v7>
+        <controller_name>
+            <longName>Controller Name</longName>
+            <type>3</type>
+            <valString>VESC</valString>
+        </controller_name>
+        <unused>
+            <longName>Not Serialized</longName>
+            <type>2</type>
+            <valInt>5</valInt>
+            <vTx>6</vTx>
+        </unused>
+    </Params>
+    <SerOrder>
+        <ser>l_current_max</ser>
+        <ser>l_temp_fet_start</ser>
+        <ser>l_erpm_start</ser>
+        <ser>motor_type</ser>
+        <ser>si_motor_poles</ser>
+        <ser>foc_sample_v0_v7</ser>
+        <ser>controller_name</ser>
+    </SerOrder>
+</ConfigParams>
+"#;
+
+const BLOB: &[u8] = &[
+    0, 0, 0xAB, 0xCD, // signature
+    0x42, 0x70, 0, 0, // l_current_max = 60
+    3, 82, // l_temp_fet_start = 85
+    3, 32, // l_erpm_start = 0.8
+    2,  // motor_type = FOC
+    14, // si_motor_poles = 14
+    0,  // foc_sample_v0_v7 = false
+    b'V', b'E', b'S', b'C', 0, // controller_name
+];
+
+#[test]
+fn parse_schema() {
+    let schema = ConfigSchema::parse(XML).unwrap();
+
+    assert_that!(schema.params().count(), eq(8));
+    assert_that!(
+        schema
+            .serialized_params()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>(),
+        eq(&[
+            "l_current_max",
+            "l_temp_fet_start",
+            "l_erpm_start",
+            "motor_type",
+            "si_motor_poles",
+            "foc_sample_v0_v7",
+            "controller_name",
+        ])
+    );
+    assert_that!(
+        schema.param("l_current_max"),
+        some(pat!(vesc::ConfigParam {
+            long_name: eq("Motor Current Max"),
+            description: eq("Maximum motor current."),
+            param_type: eq(&ParamType::Double),
+            tx_type: eq(&TxType::Double32Auto),
+            scale: eq(&1.0),
+            suffix: eq(" A"),
+            default: eq(&ConfigValue::Double(60.0)),
+            ..
+        }))
+    );
+    assert_that!(
+        schema.param("motor_type"),
+        some(pat!(vesc::ConfigParam {
+            param_type: eq(&ParamType::Enum),
+            enum_names: elements_are![eq("BLDC"), eq("DC"), eq("FOC")],
+            default: eq(&ConfigValue::Int(2)),
+            ..
+        }))
+    );
+    assert_that!(schema.param("missing"), none());
+}
+
+#[test]
+fn parse_schema_unknown_ser() {
+    let xml = "<ConfigParams><Params/><SerOrder><ser>foo</ser></SerOrder></ConfigParams>";
+
+    assert_that!(
+        ConfigSchema::parse(xml),
+        err(eq(&ConfigError::UnknownParam { name: "foo".into() }))
+    );
+}
+
+#[test]
+fn parse_schema_invalid_param() {
+    let xml = "<ConfigParams><Params><foo><type>9</type></foo></Params></ConfigParams>";
+
+    assert_that!(
+        ConfigSchema::parse(xml),
+        err(eq(&ConfigError::InvalidParam { name: "foo".into() }))
+    );
+    assert_that!(
+        ConfigSchema::parse("<Foo/>"),
+        err(eq(&ConfigError::MissingParams))
+    );
+    assert_that!(ConfigSchema::parse("<Foo"), err(pat!(ConfigError::Xml(_))));
+}
+
+#[test]
+fn decode_config_values() {
+    let schema = ConfigSchema::parse(XML).unwrap();
+    let values = ConfigValues::decode(&schema, BLOB).unwrap();
+
+    assert_that!(values.signature(), eq(0xABCD));
+    assert_that!(
+        values.get("l_current_max"),
+        some(eq(&ConfigValue::Double(60.0)))
+    );
+    assert_that!(
+        values.get("l_temp_fet_start"),
+        some(eq(&ConfigValue::Double(85.0)))
+    );
+    assert_that!(
+        values.get("l_erpm_start").and_then(ConfigValue::as_f64),
+        some(near(0.8, 1e-9))
+    );
+    assert_that!(values.get("motor_type"), some(eq(&ConfigValue::Int(2))));
+    assert_that!(
+        values.get("si_motor_poles"),
+        some(eq(&ConfigValue::Int(14)))
+    );
+    assert_that!(
+        values.get("foc_sample_v0_v7"),
+        some(eq(&ConfigValue::Bool(false)))
+    );
+    assert_that!(
+        values.get("controller_name").and_then(ConfigValue::as_str),
+        some(eq("VESC"))
+    );
+    assert_that!(values.get("unused"), none());
+    assert_that!(values.iter().count(), eq(7));
+}
+
+#[test]
+fn decode_config_values_invalid_length() {
+    let schema = ConfigSchema::parse(XML).unwrap();
+
+    assert_that!(
+        ConfigValues::decode(&schema, &BLOB[..BLOB.len() - 3]),
+        err(eq(&ConfigError::Decode(vesc::DecodeError::IncompleteData)))
+    );
+    assert_that!(
+        ConfigValues::decode(&schema, &[BLOB, &[1, 2]].concat()),
+        err(eq(&ConfigError::TrailingData { len: 2 }))
+    );
+}
+
+#[test]
+fn encode_config_values() {
+    let schema = ConfigSchema::parse(XML).unwrap();
+    let mut values = ConfigValues::decode(&schema, BLOB).unwrap();
+
+    assert_that!(values.encode(), ok(eq(BLOB)));
+
+    values
+        .set("l_current_max", ConfigValue::Double(-12.5))
+        .unwrap();
+    values.set("si_motor_poles", ConfigValue::Int(-2)).unwrap();
+    values
+        .set("foc_sample_v0_v7", ConfigValue::Bool(true))
+        .unwrap();
+    values
+        .set("controller_name", ConfigValue::String("X".into()))
+        .unwrap();
+
+    assert_that!(
+        values.encode(),
+        ok(eq(&[
+            0, 0, 0xAB, 0xCD, 0xC1, 0x48, 0, 0, 3, 82, 3, 32, 2, 0xFE, 1, b'X', 0
+        ]))
+    );
+}
+
+#[test]
+fn encode_config_values_defaults() {
+    let schema = ConfigSchema::parse(XML).unwrap();
+    let values = ConfigValues::with_defaults(&schema, 0xABCD);
+
+    assert_that!(values.encode(), ok(eq(BLOB)));
+}
+
+#[test]
+fn set_config_value_errors() {
+    let schema = ConfigSchema::parse(XML).unwrap();
+    let mut values = ConfigValues::with_defaults(&schema, 0);
+
+    assert_that!(
+        values.set("l_current_max", ConfigValue::Int(1)),
+        err(eq(&ConfigError::TypeMismatch {
+            name: "l_current_max".into()
+        }))
+    );
+    assert_that!(
+        values.set("unused", ConfigValue::Int(1)),
+        err(eq(&ConfigError::UnknownParam {
+            name: "unused".into()
+        }))
+    );
+
+    values.set("motor_type", ConfigValue::Int(256)).unwrap();
+    assert_that!(
+        values.encode(),
+        err(eq(&ConfigError::ValueOutOfRange {
+            name: "motor_type".into()
+        }))
+    );
+}